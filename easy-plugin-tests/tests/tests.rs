@@ -1,5 +1,6 @@
 #![cfg_attr(not(feature="syntex"), feature(plugin))]
 #![cfg_attr(not(feature="syntex"), feature(plugin_registrar))]
+#![cfg_attr(not(feature="syntex"), feature(pub_restricted))]
 #![cfg_attr(not(feature="syntex"), feature(rustc_private))]
 
 #![cfg_attr(not(feature="syntex"), plugin(easy_plugin))]
@@ -8,10 +9,14 @@
 extern crate syntex as rustc_plugin;
 #[cfg(feature="syntex")]
 extern crate syntex_syntax as syntax;
+#[cfg(feature="syntex")]
+extern crate syntex_errors as rustc_errors;
 #[cfg(not(feature="syntex"))]
 extern crate rustc_plugin;
 #[cfg(not(feature="syntex"))]
 extern crate syntax;
+#[cfg(not(feature="syntex"))]
+extern crate rustc_errors as rustc_errors;
 
 #[cfg_attr(not(feature="syntex"), allow(plugin_as_library))]
 #[macro_use]
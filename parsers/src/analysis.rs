@@ -0,0 +1,482 @@
+// Copyright 2016 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Argument specification analysis.
+
+use syntax::codemap::{Span};
+use syntax::parse::{ParseSess};
+use syntax::parse::token::{self, BinOpToken, DelimToken, Token};
+use syntax::tokenstream::{TokenTree};
+
+use super::specification::{self, Amount, Sequence, Specifier};
+use super::utility::{self, PluginResult};
+
+//================================================
+// Enums
+//================================================
+
+// Start _________________________________________
+
+/// A kind of token that may start a piece of an argument specification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Start {
+    /// A specific token.
+    Token(Token),
+    /// Any binary operator.
+    BinOp,
+    /// Any identifier.
+    Ident,
+    /// Any lifetime.
+    Lifetime,
+    /// Any literal.
+    Literal,
+    /// Any opening delimiter.
+    OpenDelim,
+    /// Any token.
+    Any,
+}
+
+impl Start {
+    //- Accessors --------------------------------
+
+    /// Returns whether this kind of token contains the supplied token.
+    fn contains(&self, token: &Token) -> bool {
+        match (self, token) {
+            (&Start::Token(ref start), token) => start == token,
+            (&Start::BinOp, &Token::BinOp(_)) | (&Start::BinOp, &Token::BinOpEq(_)) => true,
+            (&Start::Ident, &Token::Ident(_)) => true,
+            (&Start::Lifetime, &Token::Lifetime(_)) => true,
+            (&Start::Literal, &Token::Literal(..)) => true,
+            (&Start::OpenDelim, &Token::OpenDelim(_)) => true,
+            (&Start::Any, _) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns whether this kind of token and the supplied kind of token have any tokens in
+    /// common.
+    fn overlaps(&self, other: &Start) -> bool {
+        match (self, other) {
+            (&Start::Token(ref token), other) | (other, &Start::Token(ref token)) =>
+                other.contains(token),
+            (&Start::Any, _) | (_, &Start::Any) => true,
+            (start, other) => start == other,
+        }
+    }
+}
+
+//================================================
+// Structs
+//================================================
+
+// First _________________________________________
+
+/// The kinds of tokens that may start a piece of an argument specification.
+#[derive(Clone, Debug)]
+struct First {
+    /// The kinds of tokens that may start the piece.
+    starts: Vec<Start>,
+    /// Whether the piece may match no tokens at all.
+    nullable: bool,
+}
+
+impl First {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `First` for a piece that always matches at least one token.
+    fn new(starts: Vec<Start>) -> First {
+        First { starts: starts, nullable: false }
+    }
+
+    /// Constructs a new `First` for the end of the arguments.
+    fn end() -> First {
+        First { starts: vec![], nullable: true }
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns whether any of the tokens that may start this piece may also start the supplied
+    /// piece.
+    fn overlaps(&self, other: &First) -> bool {
+        self.starts.iter().any(|s| other.starts.iter().any(|o| s.overlaps(o)))
+    }
+
+    //- Consumers --------------------------------
+
+    /// Returns the kinds of tokens that may start this piece followed by the supplied piece.
+    fn then(mut self, other: &First) -> First {
+        if self.nullable {
+            self.starts.extend(other.starts.iter().cloned());
+            self.nullable = other.nullable;
+        }
+        self
+    }
+
+    /// Returns the kinds of tokens that may start either this piece or the supplied piece.
+    fn or(mut self, other: &First) -> First {
+        self.starts.extend(other.starts.iter().cloned());
+        self.nullable = self.nullable || other.nullable;
+        self
+    }
+}
+
+//================================================
+// Functions
+//================================================
+
+/// Returns the kinds of tokens that may start an expression.
+fn expr_starts() -> Vec<Start> {
+    vec![
+        Start::Ident,
+        Start::Lifetime,
+        Start::Literal,
+        Start::OpenDelim,
+        Start::Token(Token::BinOp(BinOpToken::And)),
+        Start::Token(Token::BinOp(BinOpToken::Minus)),
+        Start::Token(Token::BinOp(BinOpToken::Or)),
+        Start::Token(Token::BinOp(BinOpToken::Star)),
+        Start::Token(Token::AndAnd),
+        Start::Token(Token::DotDot),
+        Start::Token(Token::Lt),
+        Start::Token(Token::ModSep),
+        Start::Token(Token::Not),
+        Start::Token(Token::OrOr),
+    ]
+}
+
+/// Returns the kinds of tokens that may continue an expression.
+fn expr_continuations() -> Vec<Start> {
+    vec![
+        Start::BinOp,
+        Start::Token(Token::AndAnd),
+        Start::Token(Token::Dot),
+        Start::Token(Token::DotDot),
+        Start::Token(Token::Eq),
+        Start::Token(Token::EqEq),
+        Start::Token(Token::Ge),
+        Start::Token(Token::Gt),
+        Start::Token(Token::Le),
+        Start::Token(Token::Lt),
+        Start::Token(Token::Ne),
+        Start::Token(Token::OpenDelim(DelimToken::Bracket)),
+        Start::Token(Token::OpenDelim(DelimToken::Paren)),
+        Start::Token(Token::OrOr),
+        Start::Token(Token::Question),
+    ]
+}
+
+/// Returns the kinds of tokens that may start the supplied specifier.
+fn specifier_first(specifier: &Specifier) -> First {
+    match *specifier {
+        Specifier::Attr(_) => First::new(vec![Start::Token(Token::Pound)]),
+        Specifier::BinOp(_) => First::new(vec![Start::BinOp]),
+        Specifier::Block(_) => First::new(vec![Start::Token(Token::OpenDelim(DelimToken::Brace))]),
+        Specifier::Delim(_) | Specifier::AnyDelimited(..) => First::new(vec![Start::OpenDelim]),
+        Specifier::Expr(_) => First::new(expr_starts()),
+        Specifier::Ident(_) | Specifier::Meta(_) => First::new(vec![Start::Ident]),
+        Specifier::Item(_) => First::new(vec![Start::Ident, Start::Token(Token::Pound)]),
+        Specifier::Lftm(_) => First::new(vec![Start::Lifetime]),
+        Specifier::Lit(_) => First::new(vec![
+            Start::Literal,
+            Start::Token(Token::Ident(token::str_to_ident("false"))),
+            Start::Token(Token::Ident(token::str_to_ident("true"))),
+        ]),
+        Specifier::Pat(_) => First::new(vec![
+            Start::Ident,
+            Start::Literal,
+            Start::Token(Token::AndAnd),
+            Start::Token(Token::BinOp(BinOpToken::And)),
+            Start::Token(Token::BinOp(BinOpToken::Minus)),
+            Start::Token(Token::Lt),
+            Start::Token(Token::ModSep),
+            Start::Token(Token::OpenDelim(DelimToken::Bracket)),
+            Start::Token(Token::OpenDelim(DelimToken::Paren)),
+            Start::Token(Token::Underscore),
+        ]),
+        Specifier::Path(_) => First::new(vec![
+            Start::Ident, Start::Token(Token::Lt), Start::Token(Token::ModSep)
+        ]),
+        Specifier::Stmt(_) => {
+            let mut starts = expr_starts();
+            starts.push(Start::Token(Token::Pound));
+            First::new(starts)
+        },
+        Specifier::Ty(_) => First::new(vec![
+            Start::Ident,
+            Start::Token(Token::AndAnd),
+            Start::Token(Token::BinOp(BinOpToken::And)),
+            Start::Token(Token::BinOp(BinOpToken::Star)),
+            Start::Token(Token::Lt),
+            Start::Token(Token::ModSep),
+            Start::Token(Token::Not),
+            Start::Token(Token::OpenDelim(DelimToken::Bracket)),
+            Start::Token(Token::OpenDelim(DelimToken::Paren)),
+            Start::Token(Token::Underscore),
+        ]),
+        Specifier::Tok(_) | Specifier::Tt(_) => First::new(vec![Start::Any]),
+        Specifier::Extractor(_, ref extractor) => specifier_first(&extractor.specifier),
+        Specifier::Conversion(_, ref conversion) => specifier_first(&conversion.specifier),
+        Specifier::Specific(ref token) => First::new(vec![Start::Token(token.clone())]),
+        Specifier::Delimited(ref delimited) =>
+            First::new(vec![Start::Token(Token::OpenDelim(delimited.delimiter))]),
+        Specifier::Sequence(_, ref sequence) => {
+            let mut first = specification_first(&sequence.specification);
+            first.nullable = first.nullable || sequence.amount != Amount::OneOrMore;
+            first
+        },
+        Specifier::Enum(_, ref enum_) => {
+            let first = First { starts: vec![], nullable: false };
+            enum_.variants.iter().fold(first, |f, v| f.or(&specification_first(&v.specification)))
+        },
+    }
+}
+
+/// Returns the kinds of tokens that may start the supplied argument specification.
+fn specification_first(specification: &[Specifier]) -> First {
+    let first = First { starts: vec![], nullable: true };
+    specification.iter().fold(first, |f, s| f.then(&specifier_first(s)))
+}
+
+/// Returns the number of token trees the supplied specifier occupies in an argument
+/// specification.
+///
+/// The number is found by tokenizing the argument specification string for the specifier, which
+/// contains the same top-level token trees as the argument specification it was parsed from.
+fn specifier_length(specifier: &Specifier) -> usize {
+    let session = ParseSess::new();
+    let string = specifier.to_string();
+    utility::parse_tts_string(&session, &string).map(|tts| tts.len()).unwrap_or(1)
+}
+
+/// Returns the specifier that matches the arguments for the supplied specifier, which is the
+/// specifier wrapped by the supplied specifier if it is an extractor or conversion.
+fn get_matching_specifier(specifier: &Specifier) -> &Specifier {
+    match *specifier {
+        Specifier::Extractor(_, ref extractor) => get_matching_specifier(&extractor.specifier),
+        Specifier::Conversion(_, ref conversion) => get_matching_specifier(&conversion.specifier),
+        ref specifier => specifier,
+    }
+}
+
+/// Returns whether the supplied token tree is a doc comment.
+fn is_doc_comment(tt: Option<&TokenTree>) -> bool {
+    match tt {
+        Some(&TokenTree::Token(_, Token::DocComment(_))) => true,
+        _ => false,
+    }
+}
+
+/// Returns the spans of the names and the token trees of the argument specifications of the
+/// variants in the supplied enumerated specifier token trees.
+fn get_variant_tts(tts: &[TokenTree]) -> Vec<(Span, &[TokenTree])> {
+    let mut variants = vec![];
+    let mut index = 0;
+    while let Some(tt) = tts.get(index) {
+        index += 1;
+        if let TokenTree::Token(span, Token::Ident(_)) = *tt {
+            variants.push((span, get_delimited_tts(tts, index)));
+            while tts.get(index).map_or(false, |tt| !tt.eq_token(Token::Comma)) {
+                index += 1;
+            }
+        }
+    }
+    variants
+}
+
+/// Returns the token trees in the delimited token tree at the supplied index, if any.
+fn get_delimited_tts(tts: &[TokenTree], index: usize) -> &[TokenTree] {
+    match tts.get(index) {
+        Some(&TokenTree::Delimited(_, ref delimited)) => &delimited.tts,
+        _ => &[],
+    }
+}
+
+/// Returns whether the first supplied specifier matches exactly the tokens matched by the second
+/// supplied specifier.
+fn subsumes(left: &Specifier, right: &Specifier) -> bool {
+    match (left, right) {
+        (&Specifier::Specific(ref left), &Specifier::Specific(ref right)) => left == right,
+        (&Specifier::Delimited(ref left), &Specifier::Delimited(ref right)) =>
+            left.delimiter == right.delimiter &&
+            subsumes_all(&left.specification, &right.specification),
+        (&Specifier::AnyDelimited(_, ref left), &Specifier::AnyDelimited(_, ref right)) |
+        (&Specifier::AnyDelimited(_, ref left), &Specifier::Delimited(
+            specification::Delimited { specification: ref right, .. }
+        )) => subsumes_all(left, right),
+        (&Specifier::Tt(_), &Specifier::Specific(ref token)) |
+        (&Specifier::Tok(_), &Specifier::Specific(ref token)) => match *token {
+            Token::OpenDelim(_) | Token::CloseDelim(_) => false,
+            _ => true,
+        },
+        (&Specifier::Tt(_), &Specifier::Block(_)) |
+        (&Specifier::Tt(_), &Specifier::Delim(_)) |
+        (&Specifier::Tt(_), &Specifier::Delimited(_)) |
+        (&Specifier::Tt(_), &Specifier::AnyDelimited(..)) |
+        (&Specifier::Delim(_), &Specifier::Block(_)) |
+        (&Specifier::Delim(_), &Specifier::Delimited(_)) |
+        (&Specifier::Delim(_), &Specifier::AnyDelimited(..)) => true,
+        (&Specifier::Tt(_), &Specifier::BinOp(_)) |
+        (&Specifier::Tt(_), &Specifier::Ident(_)) |
+        (&Specifier::Tt(_), &Specifier::Lftm(_)) |
+        (&Specifier::Tt(_), &Specifier::Tok(_)) |
+        (&Specifier::Tok(_), &Specifier::BinOp(_)) |
+        (&Specifier::Tok(_), &Specifier::Ident(_)) |
+        (&Specifier::Tok(_), &Specifier::Lftm(_)) => true,
+        (&Specifier::Ident(_), &Specifier::Specific(Token::Ident(_))) |
+        (&Specifier::Lftm(_), &Specifier::Specific(Token::Lifetime(_))) => true,
+        (left, right) => match (left.get_type(), right.get_type()) {
+            (Some(left), Some(right)) => left == right,
+            _ => false,
+        },
+    }
+}
+
+/// Returns whether each specifier in the first supplied argument specification subsumes the
+/// corresponding specifier in the second supplied argument specification.
+fn subsumes_all(left: &[Specifier], right: &[Specifier]) -> bool {
+    left.len() == right.len() && left.iter().zip(right.iter()).all(|(l, r)| subsumes(l, r))
+}
+
+/// Returns whether the first supplied variant argument specification will always be chosen
+/// over the second supplied variant argument specification.
+fn shadows(left: &[Specifier], right: &[Specifier]) -> bool {
+    if specification_first(left).nullable {
+        return true;
+    }
+    left.len() <= right.len() && subsumes_all(left, &right[..left.len()])
+}
+
+/// Analyzes the supplied sequence.
+fn analyze_sequence(
+    span: Span,
+    tts: &[TokenTree],
+    sequence: &Sequence,
+    follow: &First,
+    warnings: &mut Vec<(Span, String)>,
+) {
+    if sequence.specification.is_empty() {
+        warnings.push((span, "empty sequences never match any arguments".into()));
+        return;
+    }
+    let first = specification_first(&sequence.specification);
+    if first.nullable && sequence.amount != Amount::ZeroOrOne && sequence.separator.is_none() {
+        let message = "sequence may repeat forever without matching any arguments";
+        warnings.push((span, message.into()));
+    }
+    match sequence.separator {
+        Some(ref separator) => {
+            if sequence.amount != Amount::OneOrMore && first.overlaps(follow) {
+                let message = "sequence may consume arguments intended for what follows";
+                warnings.push((span, message.into()));
+            }
+            let separator = specification_first(separator);
+            if sequence.trailing && separator.overlaps(follow) {
+                let message = "sequence may consume a separator intended for what follows";
+                warnings.push((span, message.into()));
+            }
+            analyze(tts, &sequence.specification, &separator.or(follow), warnings);
+        },
+        None => {
+            if first.overlaps(follow) {
+                let message = "sequence may consume arguments intended for what follows";
+                warnings.push((span, message.into()));
+            }
+            analyze(tts, &sequence.specification, follow, warnings);
+        },
+    }
+}
+
+/// Analyzes the supplied argument specification, assuming the supplied piece follows it.
+fn analyze(
+    tts: &[TokenTree],
+    specification: &[Specifier],
+    follow: &First,
+    warnings: &mut Vec<(Span, String)>,
+) {
+    let continuations = First::new(expr_continuations());
+    let mut index = 0;
+    for (position, specifier) in specification.iter().enumerate() {
+        while is_doc_comment(tts.get(index)) {
+            index += 1;
+        }
+        let length = specifier_length(specifier);
+        let span = match (tts.get(index), tts.get(index + length - 1)) {
+            (Some(start), Some(end)) => utility::span_spans(start.get_span(), end.get_span()),
+            _ => utility::span_tts(tts),
+        };
+        let specifier_follow = specification_first(&specification[position + 1..]).then(follow);
+        if let Specifier::Expr(_) = *get_matching_specifier(specifier) {
+            if specifier_follow.overlaps(&continuations) {
+                let message = format!("`{}` may consume the operator that follows it", specifier);
+                warnings.push((span, message));
+            }
+        }
+        match *specifier {
+            Specifier::Delimited(ref delimited) => {
+                let tts = get_delimited_tts(tts, index);
+                analyze(tts, &delimited.specification, &First::end(), warnings);
+            },
+            Specifier::AnyDelimited(ref name, ref specification) => {
+                let tts = get_delimited_tts(tts, index + if name.is_some() { 3 } else { 1 });
+                analyze(tts, specification, &First::end(), warnings);
+            },
+            Specifier::Sequence(ref name, ref sequence) => {
+                let tts = get_delimited_tts(tts, index + if name.is_some() { 3 } else { 1 });
+                analyze_sequence(span, tts, sequence, &specifier_follow, warnings);
+            },
+            Specifier::Enum(_, ref enum_) => {
+                let variants = &enum_.variants;
+                let tts = get_variant_tts(get_delimited_tts(tts, index + length - 1));
+                for (subindex, variant) in variants.iter().enumerate() {
+                    let (subspan, subtts) = tts.get(subindex).cloned().unwrap_or((span, &[][..]));
+                    let shadow = variants[..subindex].iter().find(|v| {
+                        shadows(&v.specification, &variant.specification)
+                    });
+                    if let Some(shadow) = shadow {
+                        let message = format!(
+                            "variant `{}` is unreachable because variant `{}` is always chosen",
+                            variant.name,
+                            shadow.name,
+                        );
+                        warnings.push((subspan, message));
+                    }
+                    analyze(subtts, &variant.specification, &specifier_follow, warnings);
+                }
+            },
+            _ => { },
+        }
+        index += length;
+    }
+}
+
+/// Analyzes the supplied argument specification which was parsed from the supplied token trees.
+///
+/// Returns warnings for pieces of the argument specification that can never match as intended,
+/// such as enumerated specifier variants that are shadowed by earlier variants, sequences that
+/// consume the arguments intended for the pieces that follow them, and empty sequences.
+pub fn analyze_specification(
+    tts: &[TokenTree], specification: &[Specifier]
+) -> Vec<(Span, String)> {
+    let mut warnings = vec![];
+    analyze(tts, specification, &First::end(), &mut warnings);
+    warnings
+}
+
+/// Analyzes the supplied argument specification string.
+pub fn analyze_specification_string(string: &str) -> PluginResult<Vec<(Span, String)>> {
+    let session = ParseSess::new();
+    let tts = try!(utility::parse_tts_string(&session, string));
+    let specification = try!(specification::parse_specification(&tts));
+    Ok(analyze_specification(&tts, &specification))
+}
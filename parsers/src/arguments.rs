@@ -16,16 +16,19 @@
 
 use std::any::{Any};
 use std::collections::{HashMap};
+use std::rc::{Rc};
 
-use syntax::codemap;
+use syntax::ast::{Ident, Lit, MetaItem, MetaItemKind, NestedMetaItem, NestedMetaItemKind};
+use syntax::codemap::{self, BytePos, Span, Spanned};
 use syntax::print::pprust;
 use syntax::parse::{ParseSess};
-use syntax::parse::token::{Token};
-use syntax::tokenstream::{TokenTree};
+use syntax::parse::token::{self, DelimToken, Token};
+use syntax::tokenstream::{self, TokenTree};
 
+use super::convert;
 use super::extractor;
 use super::{PluginResult};
-use super::specification::{Amount, Sequence, Specifier, Variant};
+use super::specification::{Amount, Sequence, Specifier, Variant, has_named_specifiers};
 use super::utility::{self, TransactionParser};
 
 //================================================
@@ -51,6 +54,11 @@ impl Arguments {
         get_sequence(self.0.get(name).unwrap())
     }
 
+    /// Returns the span of the named sequence containing named specifiers with the supplied name.
+    pub fn get_sequence_span(&self, name: &str) -> Span {
+        self.0.get(name).unwrap().downcast_ref::<Spanned<Vec<Box<Any>>>>().unwrap().span
+    }
+
     /// Returns the enum arguments with the supplied name.
     pub fn get_enum(&self, name: &str) -> EnumArguments {
         get_enum(self.0.get(name).unwrap())
@@ -86,6 +94,16 @@ impl<'a> SequenceArguments<'a> {
         self.0.into_iter().map(get_enum).map(f).collect()
     }
 
+    /// Returns the arguments as an `Option` of named sequence occurrences.
+    pub fn into_item_option(self) -> Option<ItemArguments<'a>> {
+        self.0.into_iter().next().map(get_item)
+    }
+
+    /// Returns the arguments as a `Vec` of named sequence occurrences.
+    pub fn into_item_vec(self) -> Vec<ItemArguments<'a>> {
+        self.0.into_iter().map(get_item).collect()
+    }
+
     /// Returns the arguments as sequences.
     pub fn into_sequence_option<T, F: Fn(SequenceArguments<'a>) -> T>(self, f: F) -> Option<T> {
         self.0.into_iter().next().map(get_sequence).map(f)
@@ -108,6 +126,30 @@ pub struct EnumArguments<'a> {
     pub arguments: &'a Arguments,
 }
 
+// Delimiter _____________________________________
+
+/// The delimiter found for a piece delimited by any delimiter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Delimiter {
+    /// The kind of delimiter.
+    pub delimiter: DelimToken,
+    /// The span of the opening delimiter.
+    pub open_span: Span,
+    /// The span of the closing delimiter.
+    pub close_span: Span,
+}
+
+// ItemArguments _________________________________
+
+/// A set of parsed arguments found in an occurrence of a named sequence.
+#[derive(Debug)]
+pub struct ItemArguments<'a> {
+    /// The span of this occurrence.
+    pub span: Span,
+    /// The arguments.
+    pub arguments: &'a Arguments,
+}
+
 //================================================
 // Functions
 //================================================
@@ -118,7 +160,12 @@ fn get<T: Any + Clone>(any: &Box<Any>) -> T {
 
 #[cfg_attr(feature="clippy", allow(needless_lifetimes))]
 fn get_sequence<'a>(any: &'a Box<Any>) -> SequenceArguments<'a> {
-    SequenceArguments(any.downcast_ref::<Vec<Box<Any>>>().unwrap().iter().collect())
+    // Named sequences containing named specifiers also store the span of the sequence.
+    let arguments = match any.downcast_ref::<Spanned<Vec<Box<Any>>>>() {
+        Some(spanned) => &spanned.node,
+        None => any.downcast_ref::<Vec<Box<Any>>>().unwrap(),
+    };
+    SequenceArguments(arguments.iter().collect())
 }
 
 #[cfg_attr(feature="clippy", allow(needless_lifetimes))]
@@ -127,6 +174,12 @@ fn get_enum<'a>(any: &'a Box<Any>) -> EnumArguments<'a> {
     EnumArguments { variant: variant, arguments: arguments }
 }
 
+#[cfg_attr(feature="clippy", allow(needless_lifetimes))]
+fn get_item<'a>(any: &'a Box<Any>) -> ItemArguments<'a> {
+    let &(span, ref arguments) = any.downcast_ref::<(Span, Arguments)>().unwrap();
+    ItemArguments { span: span, arguments: arguments }
+}
+
 /// Returns whether the supplied tokens are equal.
 fn mtwt_eq(left: &Token, right: &Token) -> bool {
     match (left, right) {
@@ -138,7 +191,8 @@ fn mtwt_eq(left: &Token, right: &Token) -> bool {
 }
 
 /// Returns `Ok` if the supplied token is next in the supplied parser.
-fn expect_specific_token(parser: &mut TransactionParser, expected: &Token) -> PluginResult<()> {
+#[doc(hidden)]
+pub fn expect_specific_token(parser: &mut TransactionParser, expected: &Token) -> PluginResult<()> {
     let description = format!("`{}`", pprust::token_to_string(expected));
     let (span, found) = try!(parser.next_token(&description, None));
     if mtwt_eq(&found, expected) {
@@ -148,71 +202,247 @@ fn expect_specific_token(parser: &mut TransactionParser, expected: &Token) -> Pl
     }
 }
 
-/// Parses sequence arguments.
-fn parse_sequence(
+/// Parses a sequence, using the supplied functions to parse each separator and occurrence.
+fn parse_sequence_impl<T, S, F>(
     parser: &mut TransactionParser,
-    sequence: &Sequence,
-    arguments: &mut Arguments,
-) -> PluginResult<usize> {
-    if sequence.specification.is_empty() {
-        return Ok(0);
-    }
-    // Insert empty sequence matches for each named specifier in the sequence.
-    for specifier in &sequence.specification {
-        if let Some(name) = specifier.get_name() {
-            arguments.0.insert(name.clone(), Box::new(Vec::<Box<Any>>::new()));
-        }
-    }
+    amount: Amount,
+    trailing: bool,
+    mut separator: Option<S>,
+    mut f: F,
+) -> PluginResult<(usize, Vec<Spanned<T>>)>
+    where S: FnMut(&mut TransactionParser) -> PluginResult<T>,
+          F: FnMut(&mut TransactionParser) -> PluginResult<()>
+{
     let mut count = 0;
+    let mut separators = vec![];
     loop {
-        parser.save();
+        let start = parser.save();
         // Check for a separator if expected.
-        if let Some(ref separator) = sequence.separator {
-            if count != 0 && !parser.eat(separator) {
-                return Ok(count);
+        let mut separated = false;
+        if let Some(ref mut separator) = separator {
+            if count != 0 {
+                match separator(parser) {
+                    Ok(value) => {
+                        let span = parser.get_span_from(start);
+                        separators.push(codemap::respan(span, value));
+                    },
+                    Err(_) => {
+                        parser.rollback(start);
+                        return Ok((count, separators));
+                    },
+                }
+                separated = true;
             }
         }
         // Attempt to parse an occurrence of the sequence.
-        let mut subarguments = Arguments(HashMap::new());
-        match parse_arguments_impl(parser, &sequence.specification, &mut subarguments) {
+        let occurrence = parser.save();
+        match f(parser) {
             Ok(()) => count += 1,
-            Err(error) => if count == 0 && sequence.amount == Amount::OneOrMore {
+            Err(error) => if count == 0 && amount == Amount::OneOrMore {
                 return Err(error);
+            } else if separated && trailing {
+                parser.rollback(occurrence);
+                return Ok((count, separators));
             } else {
-                parser.rollback();
-                return Ok(count);
+                if separated {
+                    separators.pop();
+                }
+                parser.rollback(start);
+                return Ok((count, separators));
             },
         }
-        // Append the occurrence arguments to the parent arguments.
-        for (k, v) in subarguments.0 {
-            let argument = arguments.0.entry(k).or_insert_with(|| Box::new(Vec::<Box<Any>>::new()));
-            argument.downcast_mut::<Vec<Box<Any>>>().unwrap().push(v);
-        }
         // Return if this sequence doesn't expect multiple occurrences.
-        if sequence.amount == Amount::ZeroOrOne {
-            return Ok(count);
+        if amount == Amount::ZeroOrOne {
+            return Ok((count, separators));
         }
     }
 }
 
-/// Parses enumerated arguments.
-fn parse_enum(
-    parser: &mut TransactionParser,
-    variants: &[Variant],
-) -> PluginResult<Box<Any>> {
-    for (index, variant) in variants.iter().enumerate() {
-        parser.save();
-        let mut subarguments = Arguments(HashMap::new());
-        match parse_arguments_impl(parser, &variant.specification, &mut subarguments) {
-            Ok(()) => return Ok(Box::new((index, subarguments))),
-            Err(error) => if index + 1 == variants.len() {
+/// Parses an opening delimiter of any kind.
+#[doc(hidden)]
+pub fn parse_open_delimiter(parser: &mut TransactionParser) -> PluginResult<Spanned<DelimToken>> {
+    match try!(parser.next_token("opening delimiter", None)) {
+        (span, Token::OpenDelim(delimiter)) => Ok(codemap::respan(span, delimiter)),
+        (span, _) => Err((span, "expected opening delimiter".into())),
+    }
+}
+
+/// Parses the closing delimiter that matches the supplied opening delimiter.
+#[doc(hidden)]
+pub fn parse_close_delimiter(
+    parser: &mut TransactionParser, open: Spanned<DelimToken>
+) -> PluginResult<Delimiter> {
+    let close = Token::CloseDelim(open.node);
+    let span = parser.get_span();
+    try!(expect_specific_token(parser, &close));
+    Ok(Delimiter { delimiter: open.node, open_span: open.span, close_span: span })
+}
+
+/// Parses an unseparated sequence, using the supplied function to parse each occurrence.
+///
+/// Returns the number of occurrences that were parsed.
+#[doc(hidden)]
+pub fn parse_sequence<F: FnMut(&mut TransactionParser) -> PluginResult<()>>(
+    parser: &mut TransactionParser, amount: Amount, f: F
+) -> PluginResult<usize> {
+    type Separator = fn(&mut TransactionParser) -> PluginResult<()>;
+    let (count, _) = try!(parse_sequence_impl(parser, amount, false, None::<Separator>, f));
+    Ok(count)
+}
+
+/// Parses a separated sequence, using the supplied functions to parse each separator and
+/// occurrence.
+///
+/// Returns the number of occurrences that were parsed and the values returned for the separators
+/// that were parsed, including the trailing separator if one is permitted and was found.
+#[doc(hidden)]
+pub fn parse_separated_sequence<T, S, F>(
+    parser: &mut TransactionParser, amount: Amount, trailing: bool, separator: S, f: F
+) -> PluginResult<(usize, Vec<Spanned<T>>)>
+    where S: FnMut(&mut TransactionParser) -> PluginResult<T>,
+          F: FnMut(&mut TransactionParser) -> PluginResult<()>
+{
+    parse_sequence_impl(parser, amount, trailing, Some(separator), f)
+}
+
+/// Parses an enum, using the supplied function to parse each variant until one succeeds.
+#[doc(hidden)]
+pub fn parse_enum<T, F: FnMut(&mut TransactionParser, usize) -> PluginResult<T>>(
+    parser: &mut TransactionParser, variants: usize, mut f: F
+) -> PluginResult<T> {
+    for index in 0..variants {
+        let start = parser.save();
+        match f(parser, index) {
+            Ok(value) => return Ok(value),
+            Err(error) => if index + 1 == variants {
                 return Err(error);
+            } else {
+                parser.rollback(start);
             },
         }
     }
     unreachable!()
 }
 
+/// Appends the supplied occurrence arguments to the supplied sequence arguments.
+fn append_arguments(arguments: &mut Arguments, occurrence: Arguments) {
+    for (k, v) in occurrence.0 {
+        let argument = arguments.0.entry(k).or_insert_with(|| Box::new(Vec::<Box<Any>>::new()));
+        argument.downcast_mut::<Vec<Box<Any>>>().unwrap().push(v);
+    }
+}
+
+/// Parses a sequence, using the supplied function to parse each occurrence.
+///
+/// The separators and the arguments found in the separators are added to the supplied arguments.
+fn parse_sequence_with<F: FnMut(&mut TransactionParser) -> PluginResult<()>>(
+    parser: &mut TransactionParser,
+    sequence: &Sequence,
+    arguments: &mut Arguments,
+    f: F,
+) -> PluginResult<usize> {
+    // Insert empty separator matches for each named specifier in the separator.
+    if let Some(ref name) = sequence.separator_name {
+        arguments.0.insert(name.clone(), Box::new(Vec::<Spanned<Token>>::new()));
+    }
+    for specifier in sequence.separator.iter().flat_map(|s| s.iter()) {
+        if let Some(name) = specifier.get_name() {
+            arguments.0.insert(name.clone(), Box::new(Vec::<Box<Any>>::new()));
+        }
+    }
+    if sequence.specification.is_empty() {
+        return Ok(0);
+    }
+    let separator = match sequence.separator {
+        Some(ref separator) => separator,
+        None => return parse_sequence(parser, sequence.amount, f),
+    };
+    let (count, separators) = try!(parse_separated_sequence(
+        parser,
+        sequence.amount,
+        sequence.trailing,
+        |parser| {
+            let mut subarguments = Arguments(HashMap::new());
+            try!(parse_arguments_impl(parser, separator, &mut subarguments));
+            Ok(subarguments)
+        },
+        f,
+    ));
+    if let Some(ref name) = sequence.separator_name {
+        let token = match separator[0] {
+            Specifier::Specific(ref token) => token,
+            _ => unreachable!(),
+        };
+        let separators = separators.iter().map(|s| {
+            codemap::respan(s.span, token.clone())
+        }).collect::<Vec<_>>();
+        arguments.0.insert(name.clone(), Box::new(separators));
+    }
+    for separator in separators {
+        append_arguments(arguments, separator.node);
+    }
+    Ok(count)
+}
+
+/// Parses sequence arguments.
+fn parse_sequence_arguments(
+    parser: &mut TransactionParser,
+    sequence: &Sequence,
+    arguments: &mut Arguments,
+) -> PluginResult<usize> {
+    if sequence.specification.is_empty() {
+        return parse_sequence_with(parser, sequence, arguments, |_| Ok(()));
+    }
+    // Insert empty sequence matches for each named specifier in the sequence.
+    for specifier in &sequence.specification {
+        if let Some(name) = specifier.get_name() {
+            arguments.0.insert(name.clone(), Box::new(Vec::<Box<Any>>::new()));
+        }
+    }
+    let mut occurrences = vec![];
+    let count = try!(parse_sequence_with(parser, sequence, arguments, |parser| {
+        let mut subarguments = Arguments(HashMap::new());
+        try!(parse_arguments_impl(parser, &sequence.specification, &mut subarguments));
+        occurrences.push(subarguments);
+        Ok(())
+    }));
+    // Append the occurrence arguments to the parent arguments.
+    for occurrence in occurrences {
+        append_arguments(arguments, occurrence);
+    }
+    Ok(count)
+}
+
+/// Parses named sequence arguments, storing the arguments for each occurrence separately.
+fn parse_sequence_items(
+    parser: &mut TransactionParser,
+    sequence: &Sequence,
+    arguments: &mut Arguments,
+) -> PluginResult<Vec<Box<Any>>> {
+    let mut items = vec![];
+    try!(parse_sequence_with(parser, sequence, arguments, |parser| {
+        let start = parser.save();
+        let mut subarguments = Arguments(HashMap::new());
+        try!(parse_arguments_impl(parser, &sequence.specification, &mut subarguments));
+        let span = parser.get_span_from(start);
+        items.push(Box::new((span, subarguments)) as Box<Any>);
+        Ok(())
+    }));
+    Ok(items)
+}
+
+/// Parses enumerated arguments.
+fn parse_enum_arguments(
+    parser: &mut TransactionParser,
+    variants: &[Variant],
+) -> PluginResult<Box<Any>> {
+    parse_enum(parser, variants.len(), |parser, index| {
+        let mut subarguments = Arguments(HashMap::new());
+        try!(parse_arguments_impl(parser, &variants[index].specification, &mut subarguments));
+        Ok(Box::new((index, subarguments)) as Box<Any>)
+    })
+}
+
 /// Actually parses the supplied arguments with the supplied argument specification.
 fn parse_arguments_impl(
     parser: &mut TransactionParser,
@@ -256,17 +486,44 @@ fn parse_arguments_impl(
                 let argument = extractor::extract(extractor, &*arguments.0.get(name).unwrap());
                 arguments.0.insert(name.clone(), try!(argument));
             },
+            Specifier::Conversion(ref name, ref conversion) => {
+                let start = parser.save();
+                try!(parse_arguments_impl(parser, &[(*conversion.specifier).clone()], arguments));
+                let span = parser.get_span_from(start);
+                let specifier = conversion.specifier.get_type().unwrap();
+                let argument = {
+                    let argument = arguments.0.get(name).unwrap();
+                    convert::convert(specifier, &conversion.ty, argument, span)
+                };
+                arguments.0.insert(name.clone(), try!(argument));
+            },
             Specifier::Specific(ref token) => try!(expect_specific_token(parser, token)),
             Specifier::Delimited(ref delimited) => {
                 try!(expect_specific_token(parser, &Token::OpenDelim(delimited.delimiter)));
                 try!(parse_arguments_impl(parser, &delimited.specification, arguments));
                 try!(expect_specific_token(parser, &Token::CloseDelim(delimited.delimiter)));
             },
+            Specifier::AnyDelimited(ref name, ref specification) => {
+                let open = try!(parse_open_delimiter(parser));
+                try!(parse_arguments_impl(parser, specification, arguments));
+                let delimiter = try!(parse_close_delimiter(parser, open));
+                if let Some(ref name) = *name {
+                    arguments.0.insert(name.clone(), Box::new(delimiter));
+                }
+            },
+            Specifier::Sequence(Some(ref name), ref sequence)
+                if has_named_specifiers(&sequence.specification) =>
+            {
+                let start = parser.save();
+                let items = try!(parse_sequence_items(parser, sequence, arguments));
+                let span = parser.get_span_from(start);
+                arguments.0.insert(name.clone(), Box::new(codemap::respan(span, items)));
+            },
             Specifier::Sequence(ref name, ref sequence) => {
-                let start = parser.get_span();
-                let count = try!(parse_sequence(parser, sequence, arguments));
+                let start = parser.save();
+                let count = try!(parse_sequence_arguments(parser, sequence, arguments));
                 if let Some(ref name) = *name {
-                    let span = utility::span_spans(start, parser.get_last_span());
+                    let span = parser.get_span_from(start);
                     if sequence.amount == Amount::ZeroOrOne {
                         let found = count != 0;
                         arguments.0.insert(name.clone(), Box::new(codemap::respan(span, found)));
@@ -275,14 +532,149 @@ fn parse_arguments_impl(
                     }
                 }
             },
-            Specifier::Enum(ref name, ref variants) => {
-                arguments.0.insert(name.clone(), try!(parse_enum(parser, variants)));
+            Specifier::Enum(ref name, ref enum_) => {
+                let variants = &enum_.variants;
+                arguments.0.insert(name.clone(), try!(parse_enum_arguments(parser, variants)));
             },
         }
     }
     Ok(())
 }
 
+/// Parses the supplied arguments with the supplied function.
+///
+/// Returns an error if the supplied function does not consume all of the supplied arguments.
+#[doc(hidden)]
+pub fn parse_arguments_with<T, F: FnOnce(&mut TransactionParser) -> PluginResult<T>>(
+    session: &ParseSess, tts: &[TokenTree], f: F
+) -> PluginResult<T> {
+    let mut parser = TransactionParser::new(session, tts);
+    let value = try!(f(&mut parser));
+    if let Some(remainder) = parser.get_remainder_span() {
+        Err((remainder, "too many arguments".into()))
+    } else {
+        Ok(value)
+    }
+}
+
+/// Returns the error in the supplied non-empty errors that occurred furthest into the arguments.
+///
+/// If there are multiple such errors, the first of these errors is returned.
+#[doc(hidden)]
+pub fn select_error(errors: Vec<(Span, String)>) -> (Span, String) {
+    // Errors for running out of arguments span all of the arguments.
+    fn position(error: &(Span, String)) -> BytePos {
+        if error.1.starts_with("unexpected end of arguments") { error.0.hi } else { error.0.lo }
+    }
+
+    let mut errors = errors.into_iter();
+    let first = errors.next().unwrap();
+    errors.fold(first, |s, e| if position(&e) > position(&s) { e } else { s })
+}
+
+/// Returns the token tree for the supplied literal.
+fn lit_to_tt(session: &ParseSess, lit: &Lit) -> PluginResult<TokenTree> {
+    let tts = try!(utility::parse_tts_string(session, &pprust::lit_to_string(lit)));
+    match tts.into_iter().next() {
+        Some(TokenTree::Token(_, token)) => Ok(TokenTree::Token(lit.span, token)),
+        _ => Err((lit.span, "expected literal".into())),
+    }
+}
+
+/// Returns the token trees for the supplied comma-separated nested meta items.
+fn nested_meta_items_to_tts(
+    session: &ParseSess, items: &[NestedMetaItem]
+) -> PluginResult<Vec<TokenTree>> {
+    let mut tts = vec![];
+    for (index, item) in items.iter().enumerate() {
+        match item.node {
+            NestedMetaItemKind::MetaItem(ref meta) =>
+                try!(meta_item_to_tts(session, meta, &mut tts)),
+            NestedMetaItemKind::Literal(ref lit) =>
+                tts.push(try!(lit_to_tt(session, lit))),
+        }
+        if let Some(next) = items.get(index + 1) {
+            tts.push(TokenTree::Token(codemap::mk_sp(item.span.hi, next.span.lo), Token::Comma));
+        }
+    }
+    Ok(tts)
+}
+
+/// Appends the token trees for the supplied meta item to the supplied token trees.
+fn meta_item_to_tts(
+    session: &ParseSess, meta: &MetaItem, tts: &mut Vec<TokenTree>
+) -> PluginResult<()> {
+    let name = meta.name();
+    let end = meta.span.lo + BytePos(name.len() as u32);
+    let ident = Token::Ident(token::str_to_ident(&name));
+    tts.push(TokenTree::Token(codemap::mk_sp(meta.span.lo, end), ident));
+    if let MetaItemKind::List(_, ref items) = meta.node {
+        let delimited = tokenstream::Delimited {
+            delim: DelimToken::Paren,
+            open_span: codemap::mk_sp(end, end + BytePos(1)),
+            tts: try!(nested_meta_items_to_tts(session, items)),
+            close_span: codemap::mk_sp(meta.span.hi - BytePos(1), meta.span.hi),
+        };
+        let span = codemap::mk_sp(end, meta.span.hi);
+        tts.push(TokenTree::Delimited(span, Rc::new(delimited)));
+    } else {
+        tts.extend(try!(meta_item_arguments(session, meta)));
+    }
+    Ok(())
+}
+
+/// Returns the token trees for the arguments of the supplied meta item.
+///
+/// The arguments of a list meta item (e.g., `foo(bar, baz = "qux")`) are the nested meta items
+/// separated by commas (e.g., `bar, baz = "qux"`), the arguments of a name-value meta item (e.g.,
+/// `foo = "bar"`) are `=` followed by the value (e.g., `= "bar"`), and a word meta item (e.g.,
+/// `foo`) has no arguments.
+pub fn meta_item_arguments(session: &ParseSess, meta: &MetaItem) -> PluginResult<Vec<TokenTree>> {
+    match meta.node {
+        MetaItemKind::Word(_) => Ok(vec![]),
+        MetaItemKind::List(_, ref items) => nested_meta_items_to_tts(session, items),
+        MetaItemKind::NameValue(ref name, ref lit) => {
+            let end = meta.span.lo + BytePos(name.len() as u32);
+            let eq = TokenTree::Token(codemap::mk_sp(end, lit.span.lo), Token::Eq);
+            Ok(vec![eq, try!(lit_to_tt(session, lit))])
+        },
+    }
+}
+
+/// Returns the span of the supplied identifier in the identifier macro invocation with the supplied
+/// span (e.g., `foo! bar { ... }`), if it can be found in the source of the invocation.
+fn get_ident_span(session: &ParseSess, span: Span, ident: Ident) -> Option<Span> {
+    let snippet = match session.codemap().span_to_snippet(span) {
+        Ok(snippet) => snippet,
+        Err(_) => return None,
+    };
+    let start = match snippet.find('!') {
+        Some(index) => index + 1,
+        None => return None,
+    };
+    let start = snippet.len() - snippet[start..].trim_left().len();
+    let name = ident.name.as_str();
+    if snippet[start..].starts_with(&*name) {
+        let lo = span.lo + BytePos(start as u32);
+        Some(codemap::mk_sp(lo, lo + BytePos(name.len() as u32)))
+    } else {
+        None
+    }
+}
+
+/// Returns the token trees for the arguments of the identifier macro invocation with the supplied
+/// span (e.g., `foo! bar { ... }`).
+///
+/// The arguments of an identifier macro invocation are the identifier (e.g., `bar`) followed by
+/// the supplied token trees (e.g., `{ ... }`). The identifier has the span it has in the source of
+/// the invocation or, if that can't be found, the span of the invocation.
+pub fn ident_macro_arguments(
+    session: &ParseSess, span: Span, ident: Ident, tts: Vec<TokenTree>
+) -> Vec<TokenTree> {
+    let span = get_ident_span(session, span, ident).unwrap_or(span);
+    Some(TokenTree::Token(span, Token::Ident(ident))).into_iter().chain(tts).collect()
+}
+
 /// Parses the supplied arguments with the supplied argument specification.
 pub fn parse_arguments(
     session: &ParseSess, tts: &[TokenTree], specification: &[Specifier]
@@ -290,12 +682,9 @@ pub fn parse_arguments(
     if tts.is_empty() && specification.is_empty() {
         return Ok(Arguments(HashMap::new()));
     }
-    let mut parser = TransactionParser::new(session, tts);
-    let mut arguments = Arguments(HashMap::new());
-    try!(parse_arguments_impl(&mut parser, specification, &mut arguments));
-    if let Some(remainder) = parser.get_remainder_span() {
-        Err((remainder, "too many arguments".into()))
-    } else {
+    parse_arguments_with(session, tts, |parser| {
+        let mut arguments = Arguments(HashMap::new());
+        try!(parse_arguments_impl(parser, specification, &mut arguments));
         Ok(arguments)
-    }
+    })
 }
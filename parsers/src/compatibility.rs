@@ -0,0 +1,339 @@
+// Copyright 2016 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Argument specification compatibility.
+
+use std::cmp;
+use std::fmt;
+use std::collections::{BTreeMap};
+
+use super::specification::{self, Amount, Specifier, Variant};
+
+//================================================
+// Enums
+//================================================
+
+// Change ________________________________________
+
+/// A change between two versions of an argument specification that may break existing users.
+///
+/// Fields are identified by their paths in the generated argument struct (e.g., `a`, `b.c` for
+/// the field `c` of the occurrences of the named sequence `b`, or `d.E.f` for the field `f` of
+/// the variant `E` of the enumerated specifier `d`). Pieces are identified by their paths in the
+/// argument specification, where named pieces are identified by their names and unnamed pieces
+/// are identified by their positions (e.g., `#1.a` for the named specifier `a` in the second
+/// piece of the argument specification).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// A field with the given type was added.
+    FieldAdded(String, String),
+    /// A field with the given type was removed.
+    FieldRemoved(String, String),
+    /// The type of a field was changed from the first given type to the second given type.
+    FieldRetyped(String, String, String),
+    /// A piece that did not previously exist is required.
+    PieceRequired(String, String),
+    /// A piece was removed.
+    PieceRemoved(String, String),
+    /// The number of times a sequence may occur was narrowed.
+    AmountNarrowed(String, Amount, Amount),
+}
+
+impl Change {
+    //- Accessors --------------------------------
+
+    /// Returns the path of the field or piece that was changed.
+    pub fn get_path(&self) -> &str {
+        match *self {
+            Change::FieldAdded(ref path, _) |
+            Change::FieldRemoved(ref path, _) |
+            Change::FieldRetyped(ref path, _, _) |
+            Change::PieceRequired(ref path, _) |
+            Change::PieceRemoved(ref path, _) |
+            Change::AmountNarrowed(ref path, _, _) => path,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Change::FieldAdded(ref path, ref ty) =>
+                write!(formatter, "field `{}` of type `{}` was added", path, ty),
+            Change::FieldRemoved(ref path, ref ty) =>
+                write!(formatter, "field `{}` of type `{}` was removed", path, ty),
+            Change::FieldRetyped(ref path, ref old, ref new) =>
+                write!(formatter, "field `{}` was changed from `{}` to `{}`", path, old, new),
+            Change::PieceRequired(ref path, ref piece) =>
+                write!(formatter, "piece `{}` at `{}` is now required", piece, path),
+            Change::PieceRemoved(ref path, ref piece) =>
+                write!(formatter, "piece `{}` at `{}` was removed", piece, path),
+            Change::AmountNarrowed(ref path, old, new) => write!(
+                formatter, "sequence at `{}` was narrowed from `{}` to `{}`", path, old, new
+            ),
+        }
+    }
+}
+
+//================================================
+// Functions
+//================================================
+
+/// Returns the supplied path joined with the supplied segment.
+fn join(path: &str, segment: &str) -> String {
+    if path.is_empty() {
+        segment.into()
+    } else {
+        format!("{}.{}", path, segment)
+    }
+}
+
+/// Returns the supplied type wrapped for a sequence occurring the supplied number of times.
+fn wrap(ty: String, amounts: &[Amount]) -> String {
+    amounts.iter().rev().fold(ty, |t, a| match *a {
+        Amount::ZeroOrOne => format!("Option<{}>", t),
+        _ => format!("Vec<{}>", t),
+    })
+}
+
+/// Collects the fields of the generated argument struct for the supplied argument specification.
+fn collect_fields(
+    specification: &[Specifier],
+    path: &str,
+    amounts: &[Amount],
+    fields: &mut BTreeMap<String, String>,
+) {
+    for specifier in specification {
+        match *specifier {
+            Specifier::Specific(_) => { },
+            Specifier::Delimited(ref delimited) =>
+                collect_fields(&delimited.specification, path, amounts, fields),
+            Specifier::AnyDelimited(ref name, ref specification) => {
+                if let Some(ref name) = *name {
+                    fields.insert(join(path, name), wrap("delimiter".into(), amounts));
+                }
+                collect_fields(specification, path, amounts, fields);
+            },
+            Specifier::Sequence(ref name, ref sequence) => {
+                let mut subamounts = amounts.to_vec();
+                subamounts.push(sequence.amount);
+                match *name {
+                    Some(ref name) => {
+                        let ty = if specification::has_named_specifiers(&sequence.specification) {
+                            wrap("item".into(), &[sequence.amount])
+                        } else if sequence.amount == Amount::ZeroOrOne {
+                            "bool".into()
+                        } else {
+                            "usize".into()
+                        };
+                        fields.insert(join(path, name), wrap(ty, amounts));
+                        collect_fields(&sequence.specification, &join(path, name), &[], fields);
+                    },
+                    None => collect_fields(&sequence.specification, path, &subamounts, fields),
+                }
+                if let Some(ref name) = sequence.separator_name {
+                    fields.insert(join(path, name), wrap("Vec<token>".into(), amounts));
+                }
+                if let Some(ref separator) = sequence.separator {
+                    let mut subamounts = amounts.to_vec();
+                    subamounts.push(Amount::ZeroOrMore);
+                    collect_fields(separator, path, &subamounts, fields);
+                }
+            },
+            Specifier::Enum(ref name, ref enum_) => {
+                let ty = enum_.ty.clone().unwrap_or_else(|| "enum".into());
+                fields.insert(join(path, name), wrap(ty, amounts));
+                for variant in &enum_.variants {
+                    let path = join(&join(path, name), &variant.name);
+                    let ty = if variant.unit { "unit variant" } else { "variant" };
+                    fields.insert(path.clone(), ty.into());
+                    collect_fields(&variant.specification, &path, &[], fields);
+                }
+            },
+            Specifier::Conversion(ref name, ref conversion) => {
+                fields.insert(join(path, name), wrap(conversion.ty.clone(), amounts));
+            },
+            ref specifier => {
+                let ty = wrap(specifier.get_type().unwrap().into(), amounts);
+                fields.insert(join(path, specifier.get_name().unwrap()), ty);
+            },
+        }
+    }
+}
+
+/// Returns whether the supplied specifier may match no arguments at all.
+fn is_optional(specifier: &Specifier) -> bool {
+    match *specifier {
+        Specifier::Sequence(_, ref sequence) =>
+            sequence.amount != Amount::OneOrMore || sequence.specification.iter().all(is_optional),
+        Specifier::Enum(_, ref enum_) =>
+            enum_.variants.iter().any(|v| v.specification.iter().all(is_optional)),
+        _ => false,
+    }
+}
+
+/// Returns whether the supplied specifiers are two versions of the same piece.
+fn is_same_piece(old: &Specifier, new: &Specifier) -> bool {
+    match (old, new) {
+        (&Specifier::Specific(ref old), &Specifier::Specific(ref new)) => old == new,
+        (&Specifier::Delimited(ref old), &Specifier::Delimited(ref new)) =>
+            old.delimiter == new.delimiter,
+        (&Specifier::AnyDelimited(ref old, _), &Specifier::AnyDelimited(ref new, _)) |
+        (&Specifier::Sequence(ref old, _), &Specifier::Sequence(ref new, _)) => old == new,
+        (&Specifier::Enum(ref old, _), &Specifier::Enum(ref new, _)) => old == new,
+        (old, new) => old.get_type().is_some() && new.get_type().is_some() &&
+            old.get_name() == new.get_name(),
+    }
+}
+
+/// Returns the path of the supplied piece.
+fn get_piece_path(path: &str, index: usize, specifier: &Specifier) -> String {
+    match specifier.get_name() {
+        Some(name) => join(path, name),
+        None => join(path, &format!("#{}", index)),
+    }
+}
+
+/// Returns the pairs of indices of the pieces in the supplied argument specifications that are
+/// two versions of the same piece.
+fn align(old: &[Specifier], new: &[Specifier]) -> Vec<(usize, usize)> {
+    // Find the longest common subsequence of pieces.
+    let mut lengths = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for o in (0..old.len()).rev() {
+        for n in (0..new.len()).rev() {
+            lengths[o][n] = if is_same_piece(&old[o], &new[n]) {
+                lengths[o + 1][n + 1] + 1
+            } else {
+                cmp::max(lengths[o + 1][n], lengths[o][n + 1])
+            };
+        }
+    }
+    let mut pairs = vec![];
+    let (mut o, mut n) = (0, 0);
+    while o < old.len() && n < new.len() {
+        if is_same_piece(&old[o], &new[n]) {
+            pairs.push((o, n));
+            o += 1;
+            n += 1;
+        } else if lengths[o + 1][n] >= lengths[o][n + 1] {
+            o += 1;
+        } else {
+            n += 1;
+        }
+    }
+    pairs
+}
+
+/// Returns whether the supplied new amount accepts fewer occurrences than the supplied old
+/// amount.
+fn is_narrowed(old: Amount, new: Amount) -> bool {
+    match (old, new) {
+        (Amount::ZeroOrMore, Amount::ZeroOrMore) |
+        (Amount::OneOrMore, Amount::OneOrMore) |
+        (Amount::OneOrMore, Amount::ZeroOrMore) |
+        (Amount::ZeroOrOne, Amount::ZeroOrOne) |
+        (Amount::ZeroOrOne, Amount::ZeroOrMore) => false,
+        _ => true,
+    }
+}
+
+/// Compares the supplied versions of the same variants.
+fn compare_variants(path: &str, old: &[Variant], new: &[Variant], changes: &mut Vec<Change>) {
+    for variant in old {
+        let path = join(path, &variant.name);
+        match new.iter().find(|v| v.name == variant.name) {
+            Some(new) => compare_pieces(&path, &variant.specification, &new.specification, changes),
+            None => changes.push(Change::PieceRemoved(path, variant.to_string())),
+        }
+    }
+}
+
+/// Compares the supplied versions of the same piece.
+fn compare_piece(path: &str, old: &Specifier, new: &Specifier, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (&Specifier::Delimited(ref old), &Specifier::Delimited(ref new)) =>
+            compare_pieces(path, &old.specification, &new.specification, changes),
+        (&Specifier::AnyDelimited(_, ref old), &Specifier::AnyDelimited(_, ref new)) =>
+            compare_pieces(path, old, new, changes),
+        (&Specifier::Sequence(_, ref old), &Specifier::Sequence(_, ref new)) => {
+            if is_narrowed(old.amount, new.amount) {
+                changes.push(Change::AmountNarrowed(path.into(), old.amount, new.amount));
+            }
+            compare_pieces(path, &old.specification, &new.specification, changes);
+            let path = join(path, "separator");
+            let none = vec![];
+            let separator = old.separator.as_ref().unwrap_or(&none);
+            compare_pieces(&path, separator, new.separator.as_ref().unwrap_or(&none), changes);
+            if old.trailing && !new.trailing {
+                let separator = specification::to_specification_string(separator);
+                changes.push(Change::PieceRemoved(path, format!("{} ?", separator)));
+            }
+        },
+        (&Specifier::Enum(_, ref old), &Specifier::Enum(_, ref new)) =>
+            compare_variants(path, &old.variants, &new.variants, changes),
+        _ => { },
+    }
+}
+
+/// Compares the supplied versions of the same argument specification.
+fn compare_pieces(path: &str, old: &[Specifier], new: &[Specifier], changes: &mut Vec<Change>) {
+    let pairs = align(old, new);
+    for (index, specifier) in old.iter().enumerate() {
+        if !pairs.iter().any(|&(o, _)| o == index) {
+            let path = get_piece_path(path, index, specifier);
+            changes.push(Change::PieceRemoved(path, specifier.to_string()));
+        }
+    }
+    for (index, specifier) in new.iter().enumerate() {
+        if !pairs.iter().any(|&(_, n)| n == index) && !is_optional(specifier) {
+            let path = get_piece_path(path, index, specifier);
+            changes.push(Change::PieceRequired(path, specifier.to_string()));
+        }
+    }
+    for (o, n) in pairs {
+        compare_piece(&get_piece_path(path, n, &new[n]), &old[o], &new[n], changes);
+    }
+}
+
+/// Compares the supplied old and new versions of an argument specification.
+///
+/// Returns the changes between the versions that may cause arguments accepted by the old version
+/// to be rejected by the new version or to be stored differently. The changes to the fields of
+/// the generated argument struct are returned first, ordered by path, followed by the changes to
+/// the pieces of the argument specification.
+pub fn compare_specifications(old: &[Specifier], new: &[Specifier]) -> Vec<Change> {
+    let mut old_fields = BTreeMap::new();
+    collect_fields(old, "", &[], &mut old_fields);
+    let mut new_fields = BTreeMap::new();
+    collect_fields(new, "", &[], &mut new_fields);
+
+    let mut changes = vec![];
+    for (path, ty) in &old_fields {
+        match new_fields.get(path) {
+            Some(new) if new != ty =>
+                changes.push(Change::FieldRetyped(path.clone(), ty.clone(), new.clone())),
+            Some(_) => { },
+            None => changes.push(Change::FieldRemoved(path.clone(), ty.clone())),
+        }
+    }
+    for (path, ty) in &new_fields {
+        if !old_fields.contains_key(path) {
+            changes.push(Change::FieldAdded(path.clone(), ty.clone()));
+        }
+    }
+    changes.sort_by(|l, r| l.get_path().cmp(r.get_path()));
+
+    compare_pieces("", old, new, &mut changes);
+    changes
+}
@@ -0,0 +1,396 @@
+// Copyright 2016 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions from parsed arguments into native values.
+
+use std::any::{Any};
+
+use syntax::ast::*;
+use syntax::codemap::{Span, Spanned};
+use syntax::ptr::{P};
+
+use super::{PluginResult};
+
+//================================================
+// Macros
+//================================================
+
+// integer! ______________________________________
+
+/// Implements `Integer` and `Convert` for an integer type.
+macro_rules! integer {
+    ($ty:ident, $suffix:expr, $signed:expr) => (
+        impl Integer for $ty {
+            fn name() -> &'static str {
+                stringify!($ty)
+            }
+
+            fn is_suffix(ty: LitIntType) -> bool {
+                ty == LitIntType::Unsuffixed || ty == $suffix
+            }
+
+            fn from_literal(value: u64, negative: bool) -> Option<$ty> {
+                let max = $ty::max_value() as u64;
+                if !negative && value <= max {
+                    Some(value as $ty)
+                } else if negative && $signed && value <= max + 1 {
+                    Some((value as i64).wrapping_neg() as $ty)
+                } else if negative && value == 0 {
+                    Some(0)
+                } else {
+                    None
+                }
+            }
+        }
+
+        impl FromLit for $ty {
+            fn from_lit(lit: &LitKind, negative: bool, span: Span) -> PluginResult<$ty> {
+                match *lit {
+                    LitKind::Int(value, ty) => convert_integer(value, ty, negative, span),
+                    LitKind::Byte(value) if !negative => {
+                        let ty = LitIntType::Unsigned(UintTy::U8);
+                        convert_integer(value as u64, ty, negative, span)
+                    },
+                    _ => Err((span, "expected integer literal".into())),
+                }
+            }
+        }
+
+        impl Convert<$ty> for (u64, LitIntType) {
+            fn convert(&self, span: Span) -> PluginResult<$ty> {
+                convert_integer(self.0, self.1, false, span)
+            }
+        }
+
+        convert!($ty);
+    );
+}
+
+// float! ________________________________________
+
+/// Implements `Float` and `Convert` for a floating-point type.
+macro_rules! float {
+    ($ty:ident, $suffix:expr) => (
+        impl Float for $ty {
+            fn name() -> &'static str {
+                stringify!($ty)
+            }
+
+            fn is_suffix(ty: Option<FloatTy>) -> bool {
+                ty.map_or(true, |t| t == $suffix)
+            }
+
+            fn from_literal(value: &str) -> Option<$ty> {
+                value.parse::<$ty>().ok().and_then(|f| if f.is_finite() { Some(f) } else { None })
+            }
+        }
+
+        impl FromLit for $ty {
+            fn from_lit(lit: &LitKind, negative: bool, span: Span) -> PluginResult<$ty> {
+                match *lit {
+                    LitKind::Float(ref value, ty) =>
+                        convert_float(value, Some(ty), negative, span),
+                    LitKind::FloatUnsuffixed(ref value) =>
+                        convert_float(value, None, negative, span),
+                    _ => Err((span, "expected float literal".into())),
+                }
+            }
+        }
+
+        impl Convert<$ty> for (String, FloatTy) {
+            fn convert(&self, span: Span) -> PluginResult<$ty> {
+                convert_float(&self.0, Some(self.1), false, span)
+            }
+        }
+
+        impl Convert<$ty> for String {
+            fn convert(&self, span: Span) -> PluginResult<$ty> {
+                convert_float(self, None, false, span)
+            }
+        }
+
+        convert!($ty);
+    );
+}
+
+// convert! ______________________________________
+
+/// Implements `Convert` for literals and literal expressions for a type that implements
+/// `FromLit`.
+macro_rules! convert {
+    ($ty:ty) => (
+        impl Convert<$ty> for Lit {
+            fn convert(&self, _: Span) -> PluginResult<$ty> {
+                <$ty as FromLit>::from_lit(&self.node, false, self.span)
+            }
+        }
+
+        impl Convert<$ty> for P<Expr> {
+            fn convert(&self, _: Span) -> PluginResult<$ty> {
+                convert_expr(self, false, self.span)
+            }
+        }
+    );
+}
+
+// convert_any! __________________________________
+
+/// Converts a type-erased argument with the supplied storage type into the type with the
+/// supplied name, which must be one of the supplied types.
+macro_rules! convert_any {
+    ($storage:ty, $name:expr, $argument:expr, $span:expr, [$($ty:ty), *]) => ({
+        $(if $name == stringify!($ty) {
+            let argument = $argument.downcast_ref::<$storage>().unwrap();
+            let value: PluginResult<$ty> = argument.convert($span);
+            return value.map(|v| Box::new(v) as Box<Any>);
+        })*
+        unreachable!()
+    });
+}
+
+//================================================
+// Traits
+//================================================
+
+// Convert _______________________________________
+
+/// A parsed argument that can be converted into a value of type `T`.
+pub trait Convert<T> {
+    /// Converts this argument into a value of type `T`.
+    ///
+    /// Errors are reported at the span of this argument or, if this argument doesn't have a span,
+    /// at the supplied span.
+    fn convert(&self, span: Span) -> PluginResult<T>;
+}
+
+impl Convert<bool> for bool {
+    fn convert(&self, _: Span) -> PluginResult<bool> {
+        Ok(*self)
+    }
+}
+
+impl Convert<char> for char {
+    fn convert(&self, _: Span) -> PluginResult<char> {
+        Ok(*self)
+    }
+}
+
+impl Convert<u8> for u8 {
+    fn convert(&self, _: Span) -> PluginResult<u8> {
+        Ok(*self)
+    }
+}
+
+impl Convert<String> for String {
+    fn convert(&self, _: Span) -> PluginResult<String> {
+        Ok(self.clone())
+    }
+}
+
+impl Convert<String> for (String, StrStyle) {
+    fn convert(&self, _: Span) -> PluginResult<String> {
+        Ok(self.0.clone())
+    }
+}
+
+impl Convert<String> for Spanned<Ident> {
+    fn convert(&self, _: Span) -> PluginResult<String> {
+        Ok(self.node.name.as_str().to_string())
+    }
+}
+
+// FromLit _______________________________________
+
+/// A type that can be constructed from a literal.
+trait FromLit: Sized {
+    /// Converts the supplied literal, which is negated if requested, into a value of this type.
+    fn from_lit(lit: &LitKind, negative: bool, span: Span) -> PluginResult<Self>;
+}
+
+impl FromLit for bool {
+    fn from_lit(lit: &LitKind, negative: bool, span: Span) -> PluginResult<bool> {
+        match *lit {
+            LitKind::Bool(value) if !negative => Ok(value),
+            _ => Err((span, "expected boolean literal".into())),
+        }
+    }
+}
+
+impl FromLit for char {
+    fn from_lit(lit: &LitKind, negative: bool, span: Span) -> PluginResult<char> {
+        match *lit {
+            LitKind::Char(value) if !negative => Ok(value),
+            _ => Err((span, "expected character literal".into())),
+        }
+    }
+}
+
+impl FromLit for String {
+    fn from_lit(lit: &LitKind, negative: bool, span: Span) -> PluginResult<String> {
+        match *lit {
+            LitKind::Str(ref value, _) if !negative => Ok(value.to_string()),
+            _ => Err((span, "expected string literal".into())),
+        }
+    }
+}
+
+// Integer _______________________________________
+
+/// An integer type.
+trait Integer: Sized {
+    /// Returns the name of this integer type (e.g., `u16`).
+    fn name() -> &'static str;
+
+    /// Returns whether the supplied integer literal suffix is permitted for this integer type.
+    fn is_suffix(ty: LitIntType) -> bool;
+
+    /// Returns the supplied integer literal value, which is negated if requested, as a value of
+    /// this integer type if it is in range.
+    fn from_literal(value: u64, negative: bool) -> Option<Self>;
+}
+
+integer!(i8, LitIntType::Signed(IntTy::I8), true);
+integer!(i16, LitIntType::Signed(IntTy::I16), true);
+integer!(i32, LitIntType::Signed(IntTy::I32), true);
+integer!(i64, LitIntType::Signed(IntTy::I64), true);
+integer!(isize, LitIntType::Signed(IntTy::Is), true);
+integer!(u8, LitIntType::Unsigned(UintTy::U8), false);
+integer!(u16, LitIntType::Unsigned(UintTy::U16), false);
+integer!(u32, LitIntType::Unsigned(UintTy::U32), false);
+integer!(u64, LitIntType::Unsigned(UintTy::U64), false);
+integer!(usize, LitIntType::Unsigned(UintTy::Us), false);
+
+// Float _________________________________________
+
+/// A floating-point type.
+trait Float: Sized {
+    /// Returns the name of this floating-point type (e.g., `f32`).
+    fn name() -> &'static str;
+
+    /// Returns whether the supplied float literal suffix is permitted for this floating-point
+    /// type.
+    fn is_suffix(ty: Option<FloatTy>) -> bool;
+
+    /// Returns the supplied float literal value as a value of this floating-point type if it is
+    /// in range.
+    fn from_literal(value: &str) -> Option<Self>;
+}
+
+float!(f32, FloatTy::F32);
+float!(f64, FloatTy::F64);
+
+convert!(bool);
+convert!(char);
+convert!(String);
+
+//================================================
+// Functions
+//================================================
+
+/// The names of the integer types arguments can be converted into.
+const INTEGERS: &'static [&'static str] = &[
+    "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize"
+];
+
+/// The names of the floating-point types arguments can be converted into.
+const FLOATS: &'static [&'static str] = &["f32", "f64"];
+
+/// Converts the supplied integer literal, which is negated if requested, into a value of type
+/// `T`.
+fn convert_integer<T: Integer>(
+    value: u64, ty: LitIntType, negative: bool, span: Span
+) -> PluginResult<T> {
+    if !T::is_suffix(ty) {
+        return Err((span, format!("expected `{}` integer literal", T::name())));
+    }
+    T::from_literal(value, negative).ok_or_else(|| {
+        (span, format!("integer literal is out of range for `{}`", T::name()))
+    })
+}
+
+/// Converts the supplied float literal, which is negated if requested, into a value of type `T`.
+fn convert_float<T: Float>(
+    value: &str, ty: Option<FloatTy>, negative: bool, span: Span
+) -> PluginResult<T> {
+    if !T::is_suffix(ty) {
+        return Err((span, format!("expected `{}` float literal", T::name())));
+    }
+    let sign = if negative { "-" } else { "" };
+    let value = format!("{}{}", sign, value.replace('_', ""));
+    T::from_literal(&value).ok_or_else(|| {
+        (span, format!("float literal is out of range for `{}`", T::name()))
+    })
+}
+
+/// Converts the supplied literal expression, which is negated if requested, into a value of type
+/// `T`.
+///
+/// Literal expressions are literals optionally wrapped in parentheses and negations.
+fn convert_expr<T: FromLit>(expr: &Expr, negative: bool, span: Span) -> PluginResult<T> {
+    match expr.node {
+        ExprKind::Lit(ref lit) => T::from_lit(&lit.node, negative, span),
+        ExprKind::Paren(ref expr) => convert_expr(expr, negative, span),
+        ExprKind::Unary(UnOp::Neg, ref expr) => convert_expr(expr, !negative, span),
+        _ => Err((expr.span, "expected literal".into())),
+    }
+}
+
+/// Returns whether the values parsed by the supplied named specifier type (e.g., `lit_int`) can
+/// be converted into the type with the supplied name (e.g., `u16`).
+pub fn is_convertible(specifier: &str, ty: &str) -> bool {
+    let integer = INTEGERS.contains(&ty);
+    let float = FLOATS.contains(&ty);
+    match specifier {
+        "expr" | "lit" => integer || float || ty == "bool" || ty == "char" || ty == "String",
+        "ident" | "lit_str" => ty == "String",
+        "lit_bool" => ty == "bool",
+        "lit_byte" => ty == "u8",
+        "lit_char" => ty == "char",
+        "lit_float" | "lit_float_unsuffixed" => float,
+        "lit_int" => integer,
+        _ => false,
+    }
+}
+
+/// Returns whether the supplied name is the name of a type arguments can be converted into.
+pub fn is_conversion_type(ty: &str) -> bool {
+    is_convertible("lit", ty)
+}
+
+/// Converts the supplied type-erased argument parsed by the supplied named specifier type into
+/// the type with the supplied name.
+#[doc(hidden)]
+pub fn convert(
+    specifier: &str, ty: &str, argument: &Box<Any>, span: Span
+) -> PluginResult<Box<Any>> {
+    match specifier {
+        "expr" => convert_any!(P<Expr>, ty, argument, span, [
+            i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64, bool, char, String
+        ]),
+        "ident" => convert_any!(Spanned<Ident>, ty, argument, span, [String]),
+        "lit" => convert_any!(Lit, ty, argument, span, [
+            i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64, bool, char, String
+        ]),
+        "lit_bool" => convert_any!(bool, ty, argument, span, [bool]),
+        "lit_byte" => convert_any!(u8, ty, argument, span, [u8]),
+        "lit_char" => convert_any!(char, ty, argument, span, [char]),
+        "lit_float" => convert_any!((String, FloatTy), ty, argument, span, [f32, f64]),
+        "lit_float_unsuffixed" => convert_any!(String, ty, argument, span, [f32, f64]),
+        "lit_int" => convert_any!((u64, LitIntType), ty, argument, span, [
+            i8, i16, i32, i64, isize, u8, u16, u32, u64, usize
+        ]),
+        "lit_str" => convert_any!((String, StrStyle), ty, argument, span, [String]),
+        _ => unreachable!(),
+    }
+}
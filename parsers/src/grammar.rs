@@ -0,0 +1,426 @@
+// Copyright 2016 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Argument specification grammars.
+
+use std::cmp;
+
+use syntax::parse::token::{DelimToken, Token};
+use syntax::print::pprust;
+
+use super::specification::{Amount, Sequence, Specifier};
+
+//================================================
+// Constants
+//================================================
+
+/// The approximate width of a character in a railroad diagram.
+const CHAR_WIDTH: usize = 9;
+/// The horizontal gap between the nodes in a railroad diagram.
+const GAP: usize = 10;
+/// The half height of a box in a railroad diagram.
+const HALF_HEIGHT: usize = 11;
+/// The vertical gap between the nodes in a railroad diagram.
+const VERTICAL_GAP: usize = 10;
+
+//================================================
+// Enums
+//================================================
+
+// Node __________________________________________
+
+/// A node in a railroad diagram.
+#[derive(Clone, Debug)]
+enum Node {
+    /// A path that matches nothing.
+    Skip,
+    /// A specific token.
+    Terminal(String),
+    /// A named specifier.
+    NonTerminal(String),
+    /// A series of nodes.
+    Sequence(Vec<Node>),
+    /// A choice between nodes, where the first node is on the main path.
+    Choice(Vec<Node>),
+    /// A node that may be repeated, with an optional separator.
+    Repeat(Box<Node>, Option<Box<Node>>),
+}
+
+impl Node {
+    //- Accessors --------------------------------
+
+    /// Returns the width of this node.
+    fn width(&self) -> usize {
+        match *self {
+            Node::Skip => 0,
+            Node::Terminal(ref text) | Node::NonTerminal(ref text) =>
+                (text.chars().count() * CHAR_WIDTH) + (GAP * 2),
+            Node::Sequence(ref nodes) => {
+                let width = nodes.iter().map(Node::width).sum::<usize>();
+                width + (GAP * nodes.len().saturating_sub(1))
+            },
+            Node::Choice(ref nodes) => nodes.iter().map(Node::width).max().unwrap_or(0) + 40,
+            Node::Repeat(ref node, ref separator) =>
+                cmp::max(node.width(), separator.as_ref().map_or(0, |s| s.width())) + 40,
+        }
+    }
+
+    /// Returns the height of this node above its main path.
+    fn up(&self) -> usize {
+        match *self {
+            Node::Skip => 0,
+            Node::Terminal(_) | Node::NonTerminal(_) => HALF_HEIGHT,
+            Node::Sequence(ref nodes) => nodes.iter().map(Node::up).max().unwrap_or(0),
+            Node::Choice(ref nodes) => nodes.first().map_or(0, Node::up),
+            Node::Repeat(ref node, _) => node.up(),
+        }
+    }
+
+    /// Returns the height of this node below its main path.
+    fn down(&self) -> usize {
+        match *self {
+            Node::Skip => 0,
+            Node::Terminal(_) | Node::NonTerminal(_) => HALF_HEIGHT,
+            Node::Sequence(ref nodes) => nodes.iter().map(Node::down).max().unwrap_or(0),
+            Node::Choice(ref nodes) => {
+                let first = nodes.first().map_or(0, Node::down);
+                let rest = nodes.iter().skip(1).map(|n| VERTICAL_GAP + n.up() + n.down());
+                first + rest.sum::<usize>()
+            },
+            Node::Repeat(ref node, ref separator) => {
+                let separator = separator.as_ref().map_or(0, |s| s.up() + s.down());
+                node.down() + VERTICAL_GAP + separator
+            },
+        }
+    }
+
+    /// Renders this node as SVG elements with the main path starting at the supplied position.
+    fn render(&self, x: usize, y: usize, svg: &mut String) {
+        match *self {
+            Node::Skip => { },
+            Node::Terminal(ref text) | Node::NonTerminal(ref text) => {
+                let width = self.width();
+                let radius = if let Node::Terminal(_) = *self { 10 } else { 0 };
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\"/>\n",
+                    x, y - HALF_HEIGHT, width, HALF_HEIGHT * 2, radius,
+                ));
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\">{}</text>\n", x + (width / 2), y + 5, escape(text)
+                ));
+            },
+            Node::Sequence(ref nodes) => {
+                let mut x = x;
+                for (index, node) in nodes.iter().enumerate() {
+                    node.render(x, y, svg);
+                    x += node.width();
+                    if index + 1 != nodes.len() {
+                        svg.push_str(&format!("<path d=\"M{} {} h{}\"/>\n", x, y, GAP));
+                        x += GAP;
+                    }
+                }
+            },
+            Node::Choice(ref nodes) => {
+                let width = self.width() - 40;
+                let mut offset = y;
+                for (index, node) in nodes.iter().enumerate() {
+                    if index != 0 {
+                        offset += nodes[index - 1].down() + VERTICAL_GAP + node.up();
+                    }
+                    svg.push_str(&format!("<path d=\"M{} {} h10 V{} h10\"/>\n", x, y, offset));
+                    node.render(x + 20, offset, svg);
+                    svg.push_str(&format!(
+                        "<path d=\"M{} {} H{} h10 V{} h10\"/>\n",
+                        x + 20 + node.width(), offset, x + 20 + width, y,
+                    ));
+                }
+            },
+            Node::Repeat(ref node, ref separator) => {
+                let width = self.width() - 40;
+                svg.push_str(&format!("<path d=\"M{} {} h20\"/>\n", x, y));
+                node.render(x + 20, y, svg);
+                svg.push_str(&format!(
+                    "<path d=\"M{} {} H{}\"/>\n", x + 20 + node.width(), y, x + width + 40
+                ));
+                let offset = y + node.down() + VERTICAL_GAP;
+                match *separator {
+                    Some(ref separator) => {
+                        let offset = offset + separator.up();
+                        let start = x + 20 + ((width - separator.width()) / 2);
+                        let end = start + separator.width();
+                        svg.push_str(&format!(
+                            "<path d=\"M{} {} V{} H{}\"/>\n", x + width + 30, y, offset, end
+                        ));
+                        separator.render(start, offset, svg);
+                        svg.push_str(&format!(
+                            "<path d=\"M{} {} H{} V{}\"/>\n", start, offset, x + 10, y
+                        ));
+                    },
+                    None => svg.push_str(&format!(
+                        "<path d=\"M{} {} V{} H{} V{}\"/>\n", x + width + 30, y, offset, x + 10, y
+                    )),
+                }
+            },
+        }
+    }
+}
+
+//================================================
+// Functions
+//================================================
+
+/// Returns the supplied text with the characters that are special in XML escaped.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Returns the supplied text as an EBNF terminal.
+///
+/// EBNF terminals cannot contain the quote that delimits them, so text that contains both kinds of
+/// quotes is returned as a concatenation of terminals that each contain only one kind of quote.
+fn to_terminal(text: &str) -> String {
+    if !text.contains('"') {
+        return format!("\"{}\"", text);
+    } else if !text.contains('\'') {
+        return format!("'{}'", text);
+    }
+    let mut pieces = vec![];
+    let mut start = 0;
+    let mut quote = None;
+    for (index, c) in text.char_indices().filter(|&(_, c)| c == '"' || c == '\'') {
+        if quote.map_or(false, |q| q != c) {
+            pieces.push(to_terminal(&text[start..index]));
+            start = index;
+        }
+        quote = Some(c);
+    }
+    pieces.push(to_terminal(&text[start..]));
+    pieces.join(", ")
+}
+
+/// Returns the name of the EBNF rule for the named piece with the supplied name in the scope
+/// with the supplied path.
+fn to_rule_name(path: &str, name: &str) -> String {
+    format!("{}_{}", path, name)
+}
+
+/// Returns the supplied delimiter as opening and closing delimiter strings.
+fn to_delimiter_strings(delimiter: DelimToken) -> (String, String) {
+    let open = pprust::token_to_string(&Token::OpenDelim(delimiter));
+    let close = pprust::token_to_string(&Token::CloseDelim(delimiter));
+    (open, close)
+}
+
+/// Returns the delimiters that may delimit a piece delimited by any delimiter.
+fn to_any_delimiters() -> Vec<(String, String)> {
+    let delimiters = [DelimToken::Paren, DelimToken::Bracket, DelimToken::Brace];
+    delimiters.iter().map(|d| to_delimiter_strings(*d)).collect()
+}
+
+/// Returns the supplied EBNF pieces as an EBNF concatenation.
+fn to_ebnf_concatenation(pieces: Vec<String>) -> String {
+    pieces.into_iter().filter(|p| !p.is_empty()).collect::<Vec<_>>().join(", ")
+}
+
+/// Returns the supplied sequence as EBNF with the supplied EBNF occurrence.
+fn to_ebnf_sequence(
+    path: &str, sequence: &Sequence, occurrence: String, rules: &mut Vec<(String, String)>
+) -> String {
+    let separator = sequence.separator.as_ref().map(|s| to_ebnf_specification(path, s, rules));
+    let body = match (sequence.amount, &separator) {
+        (Amount::ZeroOrOne, _) => format!("[ {} ]", occurrence),
+        (Amount::ZeroOrMore, &None) => format!("{{ {} }}", occurrence),
+        (Amount::ZeroOrMore, &Some(ref separator)) =>
+            format!("[ {}, {{ {}, {} }} ]", occurrence, separator, occurrence),
+        (Amount::OneOrMore, &None) => format!("{}, {{ {} }}", occurrence, occurrence),
+        (Amount::OneOrMore, &Some(ref separator)) =>
+            format!("{}, {{ {}, {} }}", occurrence, separator, occurrence),
+    };
+    match separator {
+        Some(ref separator) if sequence.trailing => format!("{}, [ {} ]", body, separator),
+        _ => body,
+    }
+}
+
+/// Returns the supplied specifier in the scope with the supplied path as EBNF, adding any rules
+/// for named pieces.
+fn to_ebnf_specifier(
+    path: &str, specifier: &Specifier, rules: &mut Vec<(String, String)>
+) -> String {
+    match *specifier {
+        Specifier::Specific(ref token) => to_terminal(&pprust::token_to_string(token)),
+        Specifier::Delimited(ref delimited) => {
+            let (open, close) = to_delimiter_strings(delimited.delimiter);
+            let specification = to_ebnf_specification(path, &delimited.specification, rules);
+            to_ebnf_concatenation(vec![to_terminal(&open), specification, to_terminal(&close)])
+        },
+        Specifier::AnyDelimited(ref name, ref specification) => {
+            let rule = name.as_ref().map(|n| to_rule_name(path, n));
+            let specification = {
+                let path = rule.as_ref().map_or(path, |r| &r[..]);
+                to_ebnf_specification(path, specification, rules)
+            };
+            let alternatives = to_any_delimiters().into_iter().map(|(o, c)| {
+                to_ebnf_concatenation(vec![to_terminal(&o), specification.clone(), to_terminal(&c)])
+            }).collect::<Vec<_>>();
+            let alternatives = alternatives.join(" | ");
+            match rule {
+                Some(rule) => {
+                    rules.push((rule.clone(), alternatives));
+                    rule
+                },
+                None => format!("( {} )", alternatives),
+            }
+        },
+        Specifier::Sequence(ref name, ref sequence) => {
+            let rule = name.as_ref().map(|n| to_rule_name(path, n));
+            let specification = {
+                let path = rule.as_ref().map_or(path, |r| &r[..]);
+                to_ebnf_specification(path, &sequence.specification, rules)
+            };
+            let occurrence = match rule {
+                Some(rule) => {
+                    rules.push((rule.clone(), specification));
+                    rule
+                },
+                None => format!("( {} )", specification),
+            };
+            to_ebnf_sequence(path, sequence, occurrence, rules)
+        },
+        Specifier::Enum(ref name, ref enum_) => {
+            let rule = to_rule_name(path, name);
+            let variants = enum_.variants.iter().map(|v| {
+                to_ebnf_specification(&to_rule_name(&rule, &v.name), &v.specification, rules)
+            }).collect::<Vec<_>>();
+            rules.push((rule.clone(), variants.join(" | ")));
+            rule
+        },
+        ref specifier => {
+            let name = specifier.get_name().unwrap();
+            format!("{} (* {} *)", specifier.get_type().unwrap(), name)
+        },
+    }
+}
+
+/// Returns the supplied argument specification in the scope with the supplied path as EBNF, adding
+/// any rules for named pieces.
+fn to_ebnf_specification(
+    path: &str, specification: &[Specifier], rules: &mut Vec<(String, String)>
+) -> String {
+    let pieces = specification.iter().map(|s| to_ebnf_specifier(path, s, rules)).collect();
+    to_ebnf_concatenation(pieces)
+}
+
+/// Returns the supplied argument specification as EBNF.
+///
+/// The argument specification is rendered as a rule with the supplied name, followed by a rule
+/// for each named sequence, enumerated specifier, and named piece delimited by any delimiter.
+/// These rules are named after the path to the piece joined with underscores, starting with the
+/// supplied name (e.g., `arguments_e_A_s` for `$s:(...)*` in the `A` variant of `$e:{...}`), so
+/// that they are unique and do not conflict with the names of types. Named specifiers are rendered as their types followed by their names in comments (e.g.,
+/// `ident (* name *)`) and sequences are rendered using optional and repeated groups. For example,
+/// the argument specification `$a:ident, $($b:expr), *` would be rendered as the EBNF below.
+///
+/// ```text
+/// arguments = ident (* a *), ",", [ ( expr (* b *) ), { ",", ( expr (* b *) ) } ] ;
+/// ```
+pub fn to_ebnf(name: &str, specification: &[Specifier]) -> String {
+    let mut rules = vec![];
+    let specification = to_ebnf_specification(name, specification, &mut rules);
+    rules.insert(0, (name.into(), specification));
+    rules.iter().map(|&(ref n, ref s)| {
+        format!("{} ;\n", format!("{} = {}", n, s).trim_right())
+    }).collect()
+}
+
+/// Returns the supplied argument specification as a railroad diagram node.
+fn to_node_specification(specification: &[Specifier]) -> Node {
+    let mut nodes = specification.iter().map(to_node_specifier).collect::<Vec<_>>();
+    match nodes.len() {
+        0 => Node::Skip,
+        1 => nodes.pop().unwrap(),
+        _ => Node::Sequence(nodes),
+    }
+}
+
+/// Returns the supplied specifier as a railroad diagram node.
+fn to_node_specifier(specifier: &Specifier) -> Node {
+    match *specifier {
+        Specifier::Specific(ref token) => Node::Terminal(pprust::token_to_string(token)),
+        Specifier::Delimited(ref delimited) => {
+            let (open, close) = to_delimiter_strings(delimited.delimiter);
+            let specification = to_node_specification(&delimited.specification);
+            Node::Sequence(vec![Node::Terminal(open), specification, Node::Terminal(close)])
+        },
+        Specifier::AnyDelimited(_, ref specification) => {
+            let specification = to_node_specification(specification);
+            Node::Choice(to_any_delimiters().into_iter().map(|(o, c)| {
+                Node::Sequence(vec![Node::Terminal(o), specification.clone(), Node::Terminal(c)])
+            }).collect())
+        },
+        Specifier::Sequence(_, ref sequence) => {
+            let occurrence = to_node_specification(&sequence.specification);
+            let separator = sequence.separator.as_ref().map(|s| to_node_specification(s));
+            let node = match sequence.amount {
+                Amount::ZeroOrOne => Node::Choice(vec![Node::Skip, occurrence]),
+                Amount::ZeroOrMore => {
+                    let node = Node::Repeat(Box::new(occurrence), separator.clone().map(Box::new));
+                    Node::Choice(vec![Node::Skip, node])
+                },
+                Amount::OneOrMore =>
+                    Node::Repeat(Box::new(occurrence), separator.clone().map(Box::new)),
+            };
+            match separator {
+                Some(separator) if sequence.trailing =>
+                    Node::Sequence(vec![node, Node::Choice(vec![Node::Skip, separator])]),
+                _ => node,
+            }
+        },
+        Specifier::Enum(_, ref enum_) => {
+            let variants = enum_.variants.iter().map(|v| to_node_specification(&v.specification));
+            Node::Choice(variants.collect())
+        },
+        ref specifier => {
+            let name = specifier.get_name().unwrap();
+            Node::NonTerminal(format!("{}: {}", name, specifier.get_type().unwrap()))
+        },
+    }
+}
+
+/// Returns the supplied argument specification as a self-contained SVG railroad diagram.
+///
+/// Specific tokens are rendered as rounded boxes and named specifiers are rendered as square
+/// boxes containing their names and types (e.g., `name: ident`).
+pub fn to_railroad_svg(specification: &[Specifier]) -> String {
+    let node = to_node_specification(specification);
+    let (width, up, down) = (node.width(), node.up(), node.down());
+    let y = 20 + up;
+    let mut svg = String::new();
+    svg.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+         viewBox=\"0 0 {0} {1}\">\n",
+        width + 60, up + down + 40,
+    ));
+    svg.push_str("<style>\n");
+    svg.push_str("path { fill: none; stroke: #000; stroke-width: 2; }\n");
+    svg.push_str("rect { fill: #fff; stroke: #000; stroke-width: 2; }\n");
+    svg.push_str("text { font: 14px monospace; text-anchor: middle; }\n");
+    svg.push_str("</style>\n");
+    svg.push_str(&format!("<path d=\"M20 {} v16 M20 {} h10\"/>\n", y - 8, y));
+    node.render(30, y, &mut svg);
+    let (end, top) = (30 + width, y - 8);
+    svg.push_str(&format!("<path d=\"M{} {} h10 M{} {} v16\"/>\n", end, y, end + 10, top));
+    svg.push_str("</svg>\n");
+    svg
+}
@@ -32,8 +32,13 @@ extern crate syntax;
 extern crate rustc_errors as rustc_errors;
 
 mod utility;
-pub use utility::{PluginResult};
+pub use utility::{PluginResult, TransactionParser};
 
+pub mod analysis;
 pub mod arguments;
+pub mod compatibility;
+pub mod convert;
 pub mod extractor;
+pub mod grammar;
 pub mod specification;
+pub mod usage;
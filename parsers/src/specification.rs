@@ -14,16 +14,29 @@
 
 //! Argument specifications.
 
-use std::collections::{HashSet};
+use std::fmt;
+use std::mem;
+use std::collections::{HashMap, HashSet};
 
 use syntax::codemap::{Span};
-use syntax::parse::{self, ParseSess};
+use syntax::parse::{ParseSess};
+use syntax::parse::lexer::comments;
 use syntax::parse::token::{self, BinOpToken, DelimToken, Token};
+use syntax::print::pprust;
 use syntax::tokenstream::{TokenTree};
 
+use super::convert;
 use super::extractor;
 use super::utility::{self, PluginResult};
 
+/// The documentation for the named pieces of an argument specification.
+///
+/// Documentation is keyed by the paths of the named pieces (e.g., `a` for the named specifier `a`,
+/// `b.c` for the named specifier `c` in the named sequence `b`, `d.E` for the variant `E` of the
+/// enumerated specifier `d`, or `d.E.f` for the named specifier `f` in that variant).
+pub type Docs = HashMap<String, Vec<String>>;
+
+
 //================================================
 // Macros
 //================================================
@@ -54,6 +67,16 @@ pub enum Amount {
     ZeroOrOne,
 }
 
+impl fmt::Display for Amount {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Amount::OneOrMore => write!(formatter, "+"),
+            Amount::ZeroOrMore => write!(formatter, "*"),
+            Amount::ZeroOrOne => write!(formatter, "?"),
+        }
+    }
+}
+
 // Specifier _____________________________________
 
 /// A piece of a plugin argument specification.
@@ -93,14 +116,18 @@ pub enum Specifier {
     Tt(String),
     /// A piece that will be filtered through an extraction function.
     Extractor(String, Extractor),
+    /// A piece that will be converted into a native type.
+    Conversion(String, Conversion),
     /// A non-variable piece.
     Specific(Token),
     /// A delimited piece.
     Delimited(Delimited),
+    /// A piece delimited by any delimiter which may be named.
+    AnyDelimited(Option<String>, Vec<Specifier>),
     /// A sequence piece which may be named.
     Sequence(Option<String>, Sequence),
     /// An enumerated piece.
-    Enum(String, Vec<Variant>),
+    Enum(String, Enum),
 }
 
 impl Specifier {
@@ -161,17 +188,155 @@ impl Specifier {
             Specifier::Tok(ref name) |
             Specifier::Tt(ref name) |
             Specifier::Extractor(ref name, _) |
+            Specifier::Conversion(ref name, _) |
             Specifier::Enum(ref name, _) => Some(name),
+            Specifier::AnyDelimited(ref name, _) |
             Specifier::Sequence(ref name, _) => name.as_ref(),
             _ => None,
         }
     }
+
+    /// Returns the type of this specifier as written in argument specifications (e.g., `ident`),
+    /// if this specifier is a simple or extractor named specifier.
+    ///
+    /// The type of a conversion named specifier is the type of the specifier being converted.
+    pub fn get_type(&self) -> Option<&str> {
+        match *self {
+            Specifier::Attr(_) => Some("attr"),
+            Specifier::BinOp(_) => Some("binop"),
+            Specifier::Block(_) => Some("block"),
+            Specifier::Delim(_) => Some("delim"),
+            Specifier::Expr(_) => Some("expr"),
+            Specifier::Ident(_) => Some("ident"),
+            Specifier::Item(_) => Some("item"),
+            Specifier::Lftm(_) => Some("lftm"),
+            Specifier::Lit(_) => Some("lit"),
+            Specifier::Meta(_) => Some("meta"),
+            Specifier::Pat(_) => Some("pat"),
+            Specifier::Path(_) => Some("path"),
+            Specifier::Stmt(_) => Some("stmt"),
+            Specifier::Ty(_) => Some("ty"),
+            Specifier::Tok(_) => Some("tok"),
+            Specifier::Tt(_) => Some("tt"),
+            Specifier::Extractor(_, ref extractor) => Some(&extractor.extractor),
+            Specifier::Conversion(_, ref conversion) => conversion.specifier.get_type(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Specifier {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Specifier::Specific(ref token) =>
+                write!(formatter, "{}", pprust::token_to_string(token)),
+            Specifier::Delimited(ref delimited) => write!(formatter, "{}", delimited),
+            Specifier::AnyDelimited(ref name, ref specification) => {
+                let specification = to_specification_string(specification);
+                match *name {
+                    Some(ref name) => write!(formatter, "${}:[{}]", name, specification),
+                    None => write!(formatter, "$[{}]", specification),
+                }
+            },
+            Specifier::Sequence(ref name, ref sequence) => match *name {
+                Some(ref name) => write!(formatter, "${}:{}", name, sequence),
+                None => write!(formatter, "${}", sequence),
+            },
+            Specifier::Enum(ref name, ref enum_) => write!(formatter, "${}:{}", name, enum_),
+            Specifier::Extractor(_, ref extractor) => write!(formatter, "{}", extractor),
+            Specifier::Conversion(_, ref conversion) => write!(formatter, "{}", conversion),
+            ref specifier => {
+                let name = specifier.get_name().unwrap();
+                write!(formatter, "${}:{}", name, specifier.get_type().unwrap())
+            },
+        }
+    }
 }
 
 //================================================
 // Structs
 //================================================
 
+// Names _________________________________________
+
+/// The named specifiers and metadata found in a scope of an argument specification.
+struct Names<'m> {
+    names: HashSet<String>,
+    path: String,
+    metadata: &'m mut Metadata,
+}
+
+impl<'m> Names<'m> {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `Names` for the top-level scope of an argument specification.
+    fn new(metadata: &'m mut Metadata) -> Names<'m> {
+        Names { names: HashSet::new(), path: String::new(), metadata: metadata }
+    }
+
+    /// Constructs a new `Names` for the scope with the supplied path relative to this scope.
+    fn child<'s>(&'s mut self, path: &str) -> Names<'s> {
+        let path = to_path(&self.path, path);
+        Names { names: HashSet::new(), path: path, metadata: &mut *self.metadata }
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns whether the supplied name has been found in this scope.
+    fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    //- Mutators ---------------------------------
+
+    /// Adds the supplied name to this scope, returning whether it was not previously found.
+    fn insert(&mut self, name: String) -> bool {
+        self.names.insert(name)
+    }
+
+    /// Adds the supplied documentation for the piece with the supplied path relative to this
+    /// scope.
+    fn document(&mut self, path: &str, docs: Vec<String>) {
+        if !docs.is_empty() {
+            self.metadata.docs.insert(to_path(&self.path, path), docs);
+        }
+    }
+
+    /// Adds the supplied attributes for the piece with the supplied path relative to this scope.
+    fn attribute(&mut self, path: &str, attributes: Vec<TokenTree>) {
+        if !attributes.is_empty() {
+            self.metadata.attributes.insert(to_path(&self.path, path), attributes);
+        }
+    }
+}
+
+// Conversion ____________________________________
+
+/// A specifier that will be converted into a native type (e.g., `$a:lit_int as u16`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conversion {
+    /// The specifier for the value that will be converted.
+    pub specifier: Box<Specifier>,
+    /// The name of the type the value will be converted into.
+    pub ty: String,
+}
+
+impl Conversion {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `Conversion`.
+    pub fn new(specifier: Box<Specifier>, ty: String) -> Conversion {
+        Conversion { specifier: specifier, ty: ty }
+    }
+}
+
+impl fmt::Display for Conversion {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = self.specifier.get_name().unwrap();
+        write!(formatter, "${}:{} as {}", name, self.specifier.get_type().unwrap(), self.ty)
+    }
+}
+
 // Delimited _____________________________________
 
 /// A delimited piece of an argument specification.
@@ -192,6 +357,42 @@ impl Delimited {
     }
 }
 
+impl fmt::Display for Delimited {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let open = pprust::token_to_string(&Token::OpenDelim(self.delimiter));
+        let close = pprust::token_to_string(&Token::CloseDelim(self.delimiter));
+        write!(formatter, "{}{}{}", open, to_specification_string(&self.specification), close)
+    }
+}
+
+// Enum __________________________________________
+
+/// An enumerated piece of an argument specification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Enum {
+    /// The name of the type generated for this enumerated piece, if one was specified.
+    pub ty: Option<String>,
+    /// The variants in this enumerated piece.
+    pub variants: Vec<Variant>,
+}
+
+impl Enum {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `Enum`.
+    pub fn new(ty: Option<String>, variants: Vec<Variant>) -> Enum {
+        Enum { ty: ty, variants: variants }
+    }
+}
+
+impl fmt::Display for Enum {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let variants = self.variants.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let ty = self.ty.as_ref().map_or("", |t| &t[..]);
+        write!(formatter, "{}{{{}}}", ty, variants.join(", "))
+    }
+}
+
 // Extractor _____________________________________
 
 /// A specifier that will be filtered through an extraction function.
@@ -212,6 +413,27 @@ impl Extractor {
     }
 }
 
+impl fmt::Display for Extractor {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "${}:{}", self.specifier.get_name().unwrap(), self.extractor)
+    }
+}
+
+// Metadata ______________________________________
+
+/// The documentation and attributes for the pieces of an argument specification.
+///
+/// Pieces are identified by paths (e.g., `a` for the named specifier `a`, `b.c` for the named
+/// specifier `c` in the named sequence `b`, `d.E` for the variant `E` of the enumerated specifier
+/// `d`, or `d.E.f` for the named specifier `f` in that variant).
+#[derive(Clone, Debug, Default)]
+pub struct Metadata {
+    /// The documentation for the named specifiers and variants.
+    pub docs: Docs,
+    /// The outer attributes for the types generated for enumerated specifiers.
+    pub attributes: HashMap<String, Vec<TokenTree>>,
+}
+
 // Sequence ______________________________________
 
 /// A sequence piece of an argument specification.
@@ -219,8 +441,13 @@ impl Extractor {
 pub struct Sequence {
     /// The number of times this sequence piece is expected to occur.
     pub amount: Amount,
-    /// The token that is expected to separate the occurrences of this sequence piece, if any.
-    pub separator: Option<Token>,
+    /// The argument specification that is expected to separate the occurrences of this sequence
+    /// piece, if any.
+    pub separator: Option<Vec<Specifier>>,
+    /// The name the separators of this sequence piece are stored with, if any.
+    pub separator_name: Option<String>,
+    /// Whether a trailing separator is permitted after the last occurrence of this sequence piece.
+    pub trailing: bool,
     /// The argument specification for this sequence piece.
     pub specification: Vec<Specifier>,
 }
@@ -228,11 +455,40 @@ pub struct Sequence {
 impl Sequence {
     //- Constructors -----------------------------
 
-    /// Constructs a new `Sequence`.
+    /// Constructs a new `Sequence` with an optional single token separator.
     pub fn new(
         amount: Amount, separator: Option<Token>, specification: Vec<Specifier>
     ) -> Sequence {
-        Sequence { amount: amount, separator: separator, specification: specification }
+        Sequence {
+            amount: amount,
+            separator: separator.map(|s| vec![Specifier::Specific(s)]),
+            separator_name: None,
+            trailing: false,
+            specification: specification,
+        }
+    }
+}
+
+impl fmt::Display for Sequence {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(formatter, "({})", to_specification_string(&self.specification)));
+        if let Some(ref name) = self.separator_name {
+            try!(write!(formatter, "${}:", name));
+        }
+        match self.separator {
+            Some(ref separator) => {
+                let separator = to_separator_string(separator);
+                if self.separator_name.is_some() && separator.starts_with(':') {
+                    try!(write!(formatter, " "));
+                }
+                try!(write!(formatter, "{} {}", separator, self.amount));
+                if self.trailing {
+                    try!(write!(formatter, "{} ?", separator));
+                }
+                Ok(())
+            },
+            None => write!(formatter, "{}", self.amount),
+        }
     }
 }
 
@@ -245,6 +501,8 @@ pub struct Variant {
     pub name: String,
     /// The argument specification for this variant.
     pub specification: Vec<Specifier>,
+    /// Whether this variant is a unit variant (i.e., it has no argument specification).
+    pub unit: bool,
 }
 
 impl Variant {
@@ -252,7 +510,23 @@ impl Variant {
 
     /// Constructs a new `Variant`.
     pub fn new(name: String, specification: Vec<Specifier>) -> Variant {
-        Variant { name: name, specification: specification }
+        Variant { name: name, specification: specification, unit: false }
+    }
+
+    /// Constructs a new unit `Variant`.
+    pub fn unit(name: String) -> Variant {
+        Variant { name: name, specification: vec![], unit: true }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.unit {
+            write!(formatter, "{}", self.name)
+        } else {
+            let specification = to_specification_string(&self.specification);
+            write!(formatter, "{}({})", self.name, specification)
+        }
     }
 }
 
@@ -276,39 +550,110 @@ fn parse_simple_specifier(
     }
 }
 
-/// Parses a sequence named specifier.
-fn parse_sequence_specifier<'i, I: Iterator<Item=&'i TokenTree>>(
-    span: Span, tts: &mut I, name: String, subtts: &[TokenTree]
+/// Parses the conversion clause (e.g., `as u16`) that may follow the supplied simple or extractor
+/// named specifier.
+///
+/// `as` is only treated as the beginning of a conversion clause when it is followed by the name
+/// of a type arguments can be converted into.
+fn parse_conversion<'i, I: Iterator<Item=&'i TokenTree> + Clone>(
+    tts: &mut I, specifier: Specifier
 ) -> PluginResult<Specifier> {
-    let mut names = HashSet::new();
-    let sequence = try!(parse_sequence(span, tts, subtts, &mut names));
-    if names.is_empty() {
-        Ok(Specifier::Sequence(Some(name), sequence))
+    let mut lookahead = tts.clone();
+    let (span, ty) = match (lookahead.next(), lookahead.next()) {
+        (Some(&TokenTree::Token(_, ref as_)), Some(&TokenTree::Token(span, Token::Ident(ty))))
+            if as_.is_keyword(token::keywords::As) => (span, ty.to_string()),
+        _ => return Ok(specifier),
+    };
+    if !convert::is_conversion_type(&ty) {
+        return Ok(specifier);
+    }
+    tts.next();
+    tts.next();
+    let name = specifier.get_name().unwrap().clone();
+    let type_ = specifier.get_type().unwrap().to_string();
+    if convert::is_convertible(&type_, &ty) {
+        Ok(Specifier::Conversion(name, Conversion::new(Box::new(specifier), ty)))
     } else {
-        Err((span, "named specifiers are disallowed in named sequences".into()))
+        Err((span, format!("`{}` values cannot be converted into `{}`", type_, ty)))
     }
 }
 
+/// Parses a sequence named specifier.
+fn parse_sequence_specifier<'i, I: Iterator<Item=&'i TokenTree> + Clone>(
+    span: Span, tts: &mut I, name: String, subtts: &[TokenTree], names: &mut Names
+) -> PluginResult<Specifier> {
+    let specification = {
+        let mut subnames = names.child(&name);
+        let specification = try!(parse_specification_impl(span, subtts, &mut subnames));
+        if subnames.contains("span") {
+            let message = "`span` is a reserved named specifier name in named sequences";
+            return Err((span, message.into()));
+        }
+        specification
+    };
+    let sequence = try!(parse_sequence(span, tts, specification, names));
+    Ok(Specifier::Sequence(Some(name), sequence))
+}
+
+/// Parses the inner attributes at the beginning of the supplied token trees.
+///
+/// The attributes are returned as the token trees of the equivalent outer attributes along with
+/// the token trees that follow the attributes.
+fn parse_inner_attributes(tts: &[TokenTree]) -> PluginResult<(Vec<TokenTree>, &[TokenTree])> {
+    let mut attributes = vec![];
+    let mut start = 0;
+    while tts.len() > start + 1 && tts[start].eq_token(Token::Pound) &&
+        tts[start + 1].eq_token(Token::Not)
+    {
+        match tts.get(start + 2) {
+            Some(&TokenTree::Delimited(_, ref delimited))
+                if delimited.delim == DelimToken::Bracket =>
+            {
+                attributes.push(tts[start].clone());
+                attributes.push(tts[start + 2].clone());
+            },
+            _ => return Err((tts[start + 1].span(), "expected `[`".into())),
+        }
+        start += 3;
+    }
+    Ok((attributes, &tts[start..]))
+}
+
 /// Parses an enumerated named specifier.
 fn parse_enumerated_specifier(
-    span: Span, name: String, subtts: &[TokenTree]
+    span: Span, name: String, ty: Option<String>, subtts: &[TokenTree], names: &mut Names
 ) -> PluginResult<Specifier> {
+    let (attributes, subtts) = try!(parse_inner_attributes(subtts));
+    names.attribute(&name, attributes);
     let mut tts = subtts.iter();
     let mut variants = vec![];
+    let mut docs = vec![];
     while let Some(tt) = tts.next() {
-        let name = match tt {
+        let variant = match tt {
+            &TokenTree::Token(_, Token::DocComment(doc)) => {
+                docs.push(comments::strip_doc_comment_decoration(&doc.as_str()));
+                continue;
+            },
             &TokenTree::Token(_, Token::Ident(ident)) => ident.to_string(),
             tt => return Err((tt.span(), "expected variant name".into())),
         };
-        match expect_tt!(span, tts) {
-            &TokenTree::Delimited(subspan, ref delimited) => {
-                let mut names = HashSet::new();
-                let specification = parse_specification_impl(subspan, &delimited.tts, &mut names);
-                variants.push(Variant::new(name, try!(specification)));
+        let path = format!("{}.{}", name, variant);
+        names.document(&path, mem::replace(&mut docs, vec![]));
+        let next = match tts.next() {
+            Some(&TokenTree::Delimited(subspan, ref delimited)) => {
+                let mut subnames = names.child(&path);
+                let subtts = &delimited.tts;
+                let specification =
+                    try!(parse_specification_impl(subspan, subtts, &mut subnames));
+                variants.push(Variant::new(variant, specification));
+                tts.next()
             },
-            tt => return Err((tt.span(), "expected variant specification".into())),
-        }
-        if let Some(tt) = tts.next() {
+            next => {
+                variants.push(Variant::unit(variant));
+                next
+            },
+        };
+        if let Some(tt) = next {
             if !tt.eq_token(Token::Comma) {
                 return Err((tt.span(), "expected `,`".into()));
             }
@@ -316,85 +661,195 @@ fn parse_enumerated_specifier(
             break;
         }
     }
-    if !variants.is_empty() {
-        Ok(Specifier::Enum(name.clone(), variants))
+    if !docs.is_empty() {
+        Err((span, "expected variant name after doc comment".into()))
+    } else if !variants.is_empty() {
+        Ok(Specifier::Enum(name.clone(), Enum::new(ty, variants)))
     } else {
         Err((span, "empty enumerated specifiers are disallowed".into()))
     }
 }
 
 /// Parses a named specifier.
-fn parse_named_specifier<'i, I: Iterator<Item=&'i TokenTree>>(
-    span: Span, tts: &mut I, name: String
+fn parse_named_specifier<'i, I: Iterator<Item=&'i TokenTree> + Clone>(
+    span: Span, tts: &mut I, name: String, names: &mut Names
 ) -> PluginResult<Specifier> {
     match expect_tt!(span, tts) {
         &TokenTree::Token(_, Token::Colon) => { },
         tt => return Err((tt.span(), "expected `:`".into())),
     }
     match expect_tt!(span, tts) {
-        &TokenTree::Token(subspan, Token::Ident(ident)) =>
-            parse_simple_specifier(subspan, name, &*ident.name.as_str()),
+        &TokenTree::Token(subspan, Token::Ident(ident)) => {
+            let specifier = parse_simple_specifier(subspan, name.clone(), &*ident.name.as_str());
+            // An enumerated specifier may be preceded by the name of the type generated for it
+            // (e.g., `$a:Kind{A(), B()}`).
+            match tts.clone().next() {
+                Some(&TokenTree::Delimited(subspan, ref delimited))
+                    if specifier.is_err() && delimited.delim == DelimToken::Brace =>
+                {
+                    tts.next();
+                    let ty = Some(ident.to_string());
+                    parse_enumerated_specifier(subspan, name, ty, &delimited.tts, names)
+                },
+                _ => parse_conversion(tts, try!(specifier)),
+            }
+        },
         &TokenTree::Delimited(subspan, ref delimited) => match delimited.delim {
-            DelimToken::Paren => parse_sequence_specifier(subspan, tts, name, &delimited.tts),
-            DelimToken::Brace => parse_enumerated_specifier(subspan, name, &delimited.tts),
+            DelimToken::Paren =>
+                parse_sequence_specifier(subspan, tts, name, &delimited.tts, names),
+            DelimToken::Brace =>
+                parse_enumerated_specifier(subspan, name, None, &delimited.tts, names),
+            DelimToken::Bracket => {
+                let specification = try!(parse_specification_impl(subspan, &delimited.tts, names));
+                Ok(Specifier::AnyDelimited(Some(name), specification))
+            },
             _ => Err((subspan, "expected named specifier specification".into())),
         },
         tt => Err((tt.span(), "expected named specifier specification".into())),
     }
 }
 
-/// Parses an unnamed sequence.
-fn parse_sequence<'i, I: Iterator<Item=&'i TokenTree>>(
-    span: Span, tts: &mut I, subtts: &[TokenTree], names: &mut HashSet<String>
+/// Parses the name of the separators of a sequence (e.g., `$name:`).
+fn parse_separator_name<'i, I: Iterator<Item=&'i TokenTree>>(
+    span: Span, tts: &mut I, names: &mut Names
+) -> PluginResult<String> {
+    let name = match expect_tt!(span, tts) {
+        &TokenTree::Token(subspan, Token::Ident(ident)) => {
+            let name = format!("{}", ident);
+            if !names.insert(name.clone()) {
+                return Err((subspan, "duplicate named specifier".into()));
+            }
+            name
+        },
+        tt => return Err((tt.span(), "expected separator name".into())),
+    };
+    match expect_tt!(span, tts) {
+        &TokenTree::Token(_, Token::Colon) => Ok(name),
+        tt => Err((tt.span(), "expected `:`".into())),
+    }
+}
+
+/// Parses the separator of a sequence (e.g., `,` or `(, and)`).
+///
+/// The names of the named specifiers in the separator, if any, are added to the supplied names.
+fn parse_separator(tt: &TokenTree, names: &mut Names) -> PluginResult<Vec<Specifier>> {
+    match *tt {
+        TokenTree::Token(_, Token::Dollar) | TokenTree::Token(_, Token::Question) =>
+            Err((tt.span(), "expected separator, `?`, `*`, or `+`".into())),
+        TokenTree::Token(_, ref token) => Ok(vec![Specifier::Specific(token.clone())]),
+        TokenTree::Delimited(subspan, ref delimited) if delimited.delim == DelimToken::Paren => {
+            let separator = try!(parse_specification_impl(subspan, &delimited.tts, names));
+            if separator.is_empty() {
+                Err((subspan, "empty separators are disallowed".into()))
+            } else {
+                Ok(separator)
+            }
+        },
+        _ => Err((tt.span(), "expected separator, `?`, `*`, or `+`".into())),
+    }
+}
+
+/// Parses the suffix of a sequence (e.g., `, *`) following the supplied argument specification.
+///
+/// The name of the separators, if any, is added to the supplied names.
+fn parse_sequence<'i, I: Iterator<Item=&'i TokenTree> + Clone>(
+    span: Span, tts: &mut I, specification: Vec<Specifier>, names: &mut Names
 ) -> PluginResult<Sequence> {
-    let specification = try!(parse_specification_impl(span, subtts, names));
-    let (amount, separator) = match expect_tt!(span, tts) {
+    let mut separator_name = None;
+    let mut tt = expect_tt!(span, tts);
+    if tt.eq_token(Token::Dollar) {
+        separator_name = Some(try!(parse_separator_name(span, tts, names)));
+        tt = expect_tt!(span, tts);
+        if let TokenTree::Token(_, Token::Question) = *tt {
+            return Err((tt.span(), "expected separator".into()));
+        }
+    }
+    let (amount, separator) = match tt {
         &TokenTree::Token(_, Token::Question) => (Amount::ZeroOrOne, None),
-        &TokenTree::Token(_, Token::BinOp(BinOpToken::Star)) => (Amount::ZeroOrMore, None),
-        &TokenTree::Token(_, Token::BinOp(BinOpToken::Plus)) => (Amount::OneOrMore, None),
-        &TokenTree::Token(_, ref separator) => match expect_tt!(span, tts) {
-            &TokenTree::Token(_, Token::BinOp(BinOpToken::Star)) =>
-                (Amount::ZeroOrMore, Some(separator.clone())),
-            &TokenTree::Token(_, Token::BinOp(BinOpToken::Plus)) =>
-                (Amount::OneOrMore, Some(separator.clone())),
-            tt => return Err((tt.span(), "expected `*` or `+`".into())),
+        &TokenTree::Token(_, Token::BinOp(BinOpToken::Star)) if separator_name.is_none() =>
+            (Amount::ZeroOrMore, None),
+        &TokenTree::Token(_, Token::BinOp(BinOpToken::Plus)) if separator_name.is_none() =>
+            (Amount::OneOrMore, None),
+        tt => {
+            let separator = try!(parse_separator(tt, names));
+            match expect_tt!(span, tts) {
+                &TokenTree::Token(_, Token::BinOp(BinOpToken::Star)) =>
+                    (Amount::ZeroOrMore, Some(separator)),
+                &TokenTree::Token(_, Token::BinOp(BinOpToken::Plus)) =>
+                    (Amount::OneOrMore, Some(separator)),
+                tt => return Err((tt.span(), "expected `*` or `+`".into())),
+            }
         },
-        tt => return Err((tt.span(), "expected separator, `?`, `*`, or `+`".into())),
     };
-    Ok(Sequence::new(amount, separator, specification))
+    if let (&Some(_), &Some(ref separator)) = (&separator_name, &separator) {
+        match separator.first() {
+            Some(&Specifier::Specific(_)) if separator.len() == 1 => { },
+            _ => return Err((tt.span(), "named separators must be a single token".into())),
+        }
+    }
+    // Check for a trailing separator (e.g., `, *, ?`). A separator followed by `?` is always
+    // interpreted as a trailing separator rather than as the separator and `?` tokens.
+    let mut trailing = false;
+    if let Some(ref separator) = separator {
+        let mut lookahead = tts.clone();
+        if let (Some(first), Some(second)) = (lookahead.next(), lookahead.next()) {
+            let mut metadata = Metadata::default();
+            let found = parse_separator(first, &mut Names::new(&mut metadata)).ok();
+            if found.as_ref() == Some(separator) && second.eq_token(Token::Question) {
+                tts.next();
+                tts.next();
+                trailing = true;
+            }
+        }
+    }
+    let mut sequence = Sequence::new(amount, separator, specification);
+    sequence.separator_name = separator_name;
+    sequence.trailing = trailing;
+    Ok(sequence)
 }
 
 /// Parses a named specifier or an unnamed sequence.
-fn parse_specifier<'i, I: Iterator<Item=&'i TokenTree>>(
-    span: Span, tts: &mut I, names: &mut HashSet<String>
+fn parse_specifier<'i, I: Iterator<Item=&'i TokenTree> + Clone>(
+    span: Span, tts: &mut I, names: &mut Names
 ) -> PluginResult<Specifier> {
     match expect_tt!(span, tts) {
         &TokenTree::Token(subspan, Token::Ident(ident)) => {
             let name = format!("{}", ident);
             if names.insert(name.clone()) {
-                parse_named_specifier(span, tts, name)
+                parse_named_specifier(span, tts, name, names)
             } else {
                 Err((subspan, "duplicate named specifier".into()))
             }
         },
-        &TokenTree::Delimited(subspan, ref delimited) => if delimited.delim == DelimToken::Paren {
-            let sequence = try!(parse_sequence(span, tts, &delimited.tts, names));
-            Ok(Specifier::Sequence(None, sequence))
-        } else {
-            Err((subspan, "expected named specifier or unnamed sequence".into()))
+        &TokenTree::Delimited(subspan, ref delimited) => match delimited.delim {
+            DelimToken::Paren => {
+                let specification = try!(parse_specification_impl(subspan, &delimited.tts, names));
+                let sequence = try!(parse_sequence(span, tts, specification, names));
+                Ok(Specifier::Sequence(None, sequence))
+            },
+            DelimToken::Bracket => {
+                let specification = try!(parse_specification_impl(subspan, &delimited.tts, names));
+                Ok(Specifier::AnyDelimited(None, specification))
+            },
+            _ => Err((subspan, "expected named specifier, unnamed sequence, or `[`".into())),
         },
-        tt => Err((tt.span(), "expected named specifier or unnamed sequence".into())),
+        tt => Err((tt.span(), "expected named specifier, unnamed sequence, or `[`".into())),
     }
 }
 
 /// Actually parses the supplied argument specification.
 fn parse_specification_impl(
-    span: Span, tts: &[TokenTree], names: &mut HashSet<String>
+    span: Span, tts: &[TokenTree], names: &mut Names
 ) -> PluginResult<Vec<Specifier>> {
     let mut tts = tts.iter();
     let mut specification = vec![];
+    let mut docs = vec![];
     while let Some(tt) = tts.next() {
         let specifier = match *tt {
+            TokenTree::Token(_, Token::DocComment(doc)) => {
+                docs.push(comments::strip_doc_comment_decoration(&doc.as_str()));
+                continue;
+            },
             TokenTree::Token(_, Token::Dollar) => try!(parse_specifier(span, &mut tts, names)),
             TokenTree::Token(_, ref token) => Specifier::Specific(token.clone()),
             TokenTree::Delimited(subspan, ref delimited) => {
@@ -403,22 +858,114 @@ fn parse_specification_impl(
             },
             _ => panic!("{:?}", tt),
         };
+        if !docs.is_empty() {
+            let message = "expected named specifier after doc comment";
+            match specifier.get_name() {
+                Some(name) => names.document(name, mem::replace(&mut docs, vec![])),
+                None => return Err((tt.span(), message.into())),
+            }
+        }
         specification.push(specifier);
     }
-    Ok(specification)
+    if docs.is_empty() {
+        Ok(specification)
+    } else {
+        Err((span, "expected named specifier after doc comment".into()))
+    }
+}
+
+/// Returns whether the supplied argument specification contains any named specifiers.
+///
+/// Named specifiers in named sequences and enumerated specifiers are not included.
+pub fn has_named_specifiers(specification: &[Specifier]) -> bool {
+    specification.iter().any(|s| match *s {
+        Specifier::Delimited(ref delimited) => has_named_specifiers(&delimited.specification),
+        Specifier::AnyDelimited(ref name, ref specification) =>
+            name.is_some() || has_named_specifiers(specification),
+        Specifier::Sequence(None, ref sequence) =>
+            sequence.separator_name.is_some() ||
+            sequence.separator.as_ref().map_or(false, |s| has_named_specifiers(s)) ||
+            has_named_specifiers(&sequence.specification),
+        ref specifier => specifier.get_name().is_some(),
+    })
 }
 
 /// Parses the supplied argument specification.
 pub fn parse_specification(tts: &[TokenTree]) -> PluginResult<Vec<Specifier>> {
-    let mut names = HashSet::new();
-    parse_specification_impl(utility::span_tts(tts), tts, &mut names)
+    parse_specification_with_metadata(tts).map(|(s, _)| s)
+}
+
+/// Parses the supplied argument specification and the documentation for its named pieces.
+pub fn parse_specification_with_docs(tts: &[TokenTree]) -> PluginResult<(Vec<Specifier>, Docs)> {
+    parse_specification_with_metadata(tts).map(|(s, m)| (s, m.docs))
+}
+
+/// Parses the supplied argument specification and the metadata for its pieces.
+///
+/// Doc comments (e.g., `/// An identifier.`) may precede named specifiers and the variants of
+/// enumerated specifiers. Inner attributes (e.g., `#![derive(PartialEq)]`) may precede the
+/// variants of enumerated specifiers.
+pub fn parse_specification_with_metadata(
+    tts: &[TokenTree]
+) -> PluginResult<(Vec<Specifier>, Metadata)> {
+    let mut metadata = Metadata::default();
+    let specification = {
+        let mut names = Names::new(&mut metadata);
+        try!(parse_specification_impl(utility::span_tts(tts), tts, &mut names))
+    };
+    Ok((specification, metadata))
+}
+
+/// Returns the supplied path joined with the supplied relative path.
+#[doc(hidden)]
+pub fn to_path(path: &str, relative: &str) -> String {
+    if path.is_empty() {
+        relative.into()
+    } else {
+        format!("{}.{}", path, relative)
+    }
+}
+
+/// Returns the supplied sequence separator as it would appear in an argument specification.
+fn to_separator_string(separator: &[Specifier]) -> String {
+    match separator.first() {
+        Some(&Specifier::Specific(ref token)) if separator.len() == 1 => match *token {
+            Token::Question |
+            Token::BinOp(BinOpToken::Star) |
+            Token::BinOp(BinOpToken::Plus) => format!("({})", pprust::token_to_string(token)),
+            _ => pprust::token_to_string(token),
+        },
+        _ => format!("({})", to_specification_string(separator)),
+    }
+}
+
+/// Returns the supplied argument specification as an argument specification string.
+///
+/// The returned string is accepted by `parse_specification_string`, which will return an
+/// argument specification equal to the supplied argument specification.
+pub fn to_specification_string(specification: &[Specifier]) -> String {
+    let specifiers = specification.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    specifiers.join(" ")
 }
 
 /// Parses the supplied argument specification string.
 pub fn parse_specification_string(string: &str) -> PluginResult<Vec<Specifier>> {
+    parse_specification_string_with_metadata(string).map(|(s, _)| s)
+}
+
+/// Parses the supplied argument specification string and the documentation for its named
+/// pieces.
+pub fn parse_specification_string_with_docs(
+    string: &str
+) -> PluginResult<(Vec<Specifier>, Docs)> {
+    parse_specification_string_with_metadata(string).map(|(s, m)| (s, m.docs))
+}
+
+/// Parses the supplied argument specification string and the metadata for its pieces.
+pub fn parse_specification_string_with_metadata(
+    string: &str
+) -> PluginResult<(Vec<Specifier>, Metadata)> {
     let session = ParseSess::new();
-    let name = "<specification>".into();
-    let mut parser = parse::new_parser_from_source_str(&session, vec![], name, string.into());
-    let tts = parser.parse_all_token_trees().unwrap();
-    parse_specification(&tts)
+    let tts = try!(utility::parse_tts_string(&session, string));
+    parse_specification_with_metadata(&tts)
 }
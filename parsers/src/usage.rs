@@ -0,0 +1,116 @@
+// Copyright 2016 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Usage strings.
+
+use std::fmt;
+
+use syntax::parse::token::{Token};
+use syntax::print::pprust;
+
+use super::specification::{Amount, Sequence, Specifier};
+
+//================================================
+// Structs
+//================================================
+
+// Usage _________________________________________
+
+/// A human-readable rendering of an argument specification.
+///
+/// Named specifiers are rendered as their names and types (e.g., `name: ident`). Sequences are
+/// rendered as bracketed groups followed by their separators and operators (e.g.,
+/// `[ field: ty ],*`), enumerated specifiers are rendered as bracketed groups of alternatives
+/// (e.g., `[ a: ident | b: meta ]`), and pieces delimited by any delimiter are rendered as
+/// `delimited( ... )`.
+#[derive(Copy, Clone, Debug)]
+pub struct Usage<'a>(pub &'a [Specifier]);
+
+impl<'a> fmt::Display for Usage<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut pieces = vec![];
+        push_specification_pieces(self.0, &mut pieces);
+        for (index, piece) in pieces.iter().enumerate() {
+            if index != 0 && !piece.starts_with(',') && !piece.starts_with(';') {
+                try!(write!(formatter, " "));
+            }
+            try!(write!(formatter, "{}", piece));
+        }
+        Ok(())
+    }
+}
+
+//================================================
+// Functions
+//================================================
+
+/// Returns the rendered suffix for the supplied sequence (e.g., `,*`).
+fn to_sequence_suffix(sequence: &Sequence) -> String {
+    let separator = match sequence.separator {
+        Some(ref separator) => match separator.first() {
+            Some(&Specifier::Specific(ref token)) if separator.len() == 1 =>
+                pprust::token_to_string(token),
+            _ => format!("({})", Usage(separator)),
+        },
+        None => "".into(),
+    };
+    let amount = match sequence.amount {
+        Amount::OneOrMore => "+",
+        Amount::ZeroOrMore => "*",
+        Amount::ZeroOrOne => "?",
+    };
+    if sequence.trailing {
+        format!("]{}{}{}?", separator, amount, separator)
+    } else {
+        format!("]{}{}", separator, amount)
+    }
+}
+
+/// Appends the rendered pieces of the supplied argument specification to the supplied pieces.
+fn push_specification_pieces(specification: &[Specifier], pieces: &mut Vec<String>) {
+    for specifier in specification {
+        match *specifier {
+            Specifier::Specific(ref token) => pieces.push(pprust::token_to_string(token)),
+            Specifier::Delimited(ref delimited) => {
+                pieces.push(pprust::token_to_string(&Token::OpenDelim(delimited.delimiter)));
+                push_specification_pieces(&delimited.specification, pieces);
+                pieces.push(pprust::token_to_string(&Token::CloseDelim(delimited.delimiter)));
+            },
+            Specifier::AnyDelimited(_, ref specification) => {
+                pieces.push("delimited(".into());
+                push_specification_pieces(specification, pieces);
+                pieces.push(")".into());
+            },
+            Specifier::Sequence(_, ref sequence) => {
+                pieces.push("[".into());
+                push_specification_pieces(&sequence.specification, pieces);
+                pieces.push(to_sequence_suffix(sequence));
+            },
+            Specifier::Enum(_, ref enum_) => {
+                pieces.push("[".into());
+                for (index, variant) in enum_.variants.iter().enumerate() {
+                    if index != 0 {
+                        pieces.push("|".into());
+                    }
+                    push_specification_pieces(&variant.specification, pieces);
+                }
+                pieces.push("]".into());
+            },
+            ref specifier => {
+                let name = specifier.get_name().unwrap();
+                pieces.push(format!("{}: {}", name, specifier.get_type().unwrap()));
+            },
+        }
+    }
+}
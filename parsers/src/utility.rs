@@ -23,7 +23,7 @@ use rustc_errors::emitter::{Emitter};
 use syntax::ext::tt::transcribe;
 use syntax::ast::*;
 use syntax::codemap::{CodeMap, Span, DUMMY_SP};
-use syntax::parse::{ParseSess, PResult};
+use syntax::parse::{self, ParseSess, PResult};
 use syntax::parse::common::{SeqSep};
 use syntax::parse::lexer::{Reader, TokenAndSpan};
 use syntax::parse::parser::{Parser, PathStyle};
@@ -43,12 +43,14 @@ pub type PluginResult<T> = Result<T, (Span, String)>;
 /// Defines a parsing method for `TransactionParser` that parses a particular AST entity.
 macro_rules! parse {
     ($name:ident($($argument:expr), *)$(.$method:ident())*, $description:expr, $ty:ty) => {
+        #[doc(hidden)]
         pub fn $name(&mut self, name: &str) -> PluginResult<(Span, $ty)> {
             self.parse_expected($description, name, |p| p.$name($($argument), *))
         }
     };
 
     (OPTION: $name:ident($($argument:expr), *)$(.$method:ident())*, $description:expr, $ty:ty) => {
+        #[doc(hidden)]
         pub fn $name(&mut self, name: &str) -> PluginResult<(Span, $ty)> {
             self.parse_expected_option($description, name, |p| p.$name($($argument), *))
         }
@@ -134,7 +136,6 @@ pub struct TransactionParser {
     session: ParseSess,
     tokens: Vec<TokenAndSpan>,
     index: usize,
-    start: usize,
     span: Span,
 }
 
@@ -150,7 +151,6 @@ impl TransactionParser {
             session: ParseSess::with_span_handler(handler, Rc::new(codemap)),
             tokens: flatten_tts(session, tts),
             index: 0,
-            start: 0,
             span: span_tts(tts),
         }
     }
@@ -167,6 +167,18 @@ impl TransactionParser {
         self.tokens.get(self.index.saturating_sub(1)).map_or(self.span, |t| t.sp)
     }
 
+    /// Returns a span that spans from the token at the supplied saved index to the last token
+    /// processed.
+    ///
+    /// If no tokens have been processed since the supplied saved index, `DUMMY_SP` is returned.
+    pub fn get_span_from(&self, start: usize) -> Span {
+        if self.index <= start {
+            DUMMY_SP
+        } else {
+            span_spans(self.tokens[start].sp, self.get_last_span())
+        }
+    }
+
     /// Returns whether the current token is the EOF token.
     fn is_eof(&self) -> bool {
         self.index + 1 >= self.tokens.len()
@@ -183,14 +195,14 @@ impl TransactionParser {
 
     //- Mutators ---------------------------------
 
-    /// Sets the saved index to the current index.
-    pub fn save(&mut self) {
-        self.start = self.index;
+    /// Returns the current index so that it may later be rolled back to.
+    pub fn save(&self) -> usize {
+        self.index
     }
 
-    /// Sets the current index to the saved index.
-    pub fn rollback(&mut self) {
-        self.index = self.start;
+    /// Sets the current index to the supplied saved index.
+    pub fn rollback(&mut self, index: usize) {
+        self.index = index;
     }
 
     /// Returns a parsing error.
@@ -277,6 +289,7 @@ impl TransactionParser {
     parse!(parse_ty(), "type", P<Ty>);
     parse!(parse_token_tree(), "token tree", TokenTree);
 
+    #[doc(hidden)]
     pub fn parse_binop(&mut self, name: &str) -> PluginResult<(Span, BinOpToken)> {
         match try!(self.next_token("binary operator", Some(name))) {
             (span, Token::BinOp(binop)) | (span, Token::BinOpEq(binop)) => Ok((span, binop)),
@@ -284,6 +297,7 @@ impl TransactionParser {
         }
     }
 
+    #[doc(hidden)]
     pub fn parse_delim(&mut self, name: &str) -> PluginResult<(Span, Delimited)> {
         let (start, delim) = match try!(self.next_token("opening delimiter", Some(name))) {
             (span, Token::OpenDelim(delim)) => (span, delim),
@@ -303,6 +317,7 @@ impl TransactionParser {
         Ok((span_spans(start, end), delimited))
     }
 
+    #[doc(hidden)]
     pub fn parse_token(&mut self, name: &str) -> PluginResult<(Span, Token)> {
         self.next_token("token", Some(name))
     }
@@ -328,6 +343,16 @@ pub fn span_spans(start: Span, end: Span) -> Span {
     Span { lo: start.lo, hi: end.hi, expn_id: start.expn_id }
 }
 
+/// Parses the supplied string into token trees.
+pub fn parse_tts_string(session: &ParseSess, string: &str) -> PluginResult<Vec<TokenTree>> {
+    let name = "<specification>".into();
+    let mut parser = parse::new_parser_from_source_str(session, vec![], name, string.into());
+    parser.parse_all_token_trees().map_err(|mut err| {
+        err.cancel();
+        (err.span.primary_span().unwrap_or(DUMMY_SP), err.message.clone())
+    })
+}
+
 /// Returns a span that spans all of the supplied token trees.
 pub fn span_tts(tts: &[TokenTree]) -> Span {
     let start = tts.get(0).map_or(DUMMY_SP, TokenTree::get_span);
@@ -0,0 +1,122 @@
+#![cfg_attr(not(feature="syntex"), feature(rustc_private))]
+
+extern crate easy_plugin_parsers;
+
+use easy_plugin_parsers::analysis::*;
+
+fn analyze(string: &str) -> Vec<String> {
+    let warnings = analyze_specification_string(string).unwrap();
+    warnings.into_iter().map(|(_, m)| m).collect()
+}
+
+#[test]
+fn test_analyze_specification_none() {
+    let empty: Vec<String> = vec![];
+    assert_eq!(analyze(""), empty);
+    assert_eq!(analyze("$a:ident $b:expr"), empty);
+    assert_eq!(analyze("$($a:ident)* ;"), empty);
+    assert_eq!(analyze("$($a:ident), + foo"), empty);
+    assert_eq!(analyze("($($a:tt)*) $b:ident"), empty);
+    assert_eq!(analyze("$($a:expr), *"), empty);
+    assert_eq!(analyze("$a:expr => $b:expr"), empty);
+    assert_eq!(analyze("$a:{A(foo $b:ident), B(bar $c:ident)}"), empty);
+    assert_eq!(analyze("$a:{A($b:ident ;), B($c:ident $d:ident)}"), empty);
+}
+
+#[test]
+fn test_analyze_specification_enum() {
+    assert_eq!(analyze("$a:{A($b:ident), B($c:ident = $d:expr)}"), vec![
+        "variant `B` is unreachable because variant `A` is always chosen",
+    ]);
+    assert_eq!(analyze("$a:{A(), B(foo)}"), vec![
+        "variant `B` is unreachable because variant `A` is always chosen",
+    ]);
+    assert_eq!(analyze("$a:{A($($b:ident)*), B(foo)}"), vec![
+        "variant `B` is unreachable because variant `A` is always chosen",
+    ]);
+    assert_eq!(analyze("$a:{A($b:tt), B(foo)}"), vec![
+        "variant `B` is unreachable because variant `A` is always chosen",
+    ]);
+    assert_eq!(analyze("$a:{A($b:ident), B(foo), C(bar)}"), vec![
+        "variant `B` is unreachable because variant `A` is always chosen",
+        "variant `C` is unreachable because variant `A` is always chosen",
+    ]);
+}
+
+#[test]
+fn test_analyze_specification_sequence() {
+    assert_eq!(analyze("$($a:ident)* foo"), vec![
+        "sequence may consume arguments intended for what follows",
+    ]);
+    assert_eq!(analyze("$($a:ident)? $b:ident"), vec![
+        "sequence may consume arguments intended for what follows",
+    ]);
+    assert_eq!(analyze("$($a:tt)* ;"), vec![
+        "sequence may consume arguments intended for what follows",
+    ]);
+    assert_eq!(analyze("$($a:ident), * foo"), vec![
+        "sequence may consume arguments intended for what follows",
+    ]);
+    assert_eq!(analyze("$($a:ident), *, ? , foo"), vec![
+        "sequence may consume a separator intended for what follows",
+    ]);
+    assert_eq!(analyze("$($($a:ident)?)*"), vec![
+        "sequence may repeat forever without matching any arguments",
+    ]);
+    assert_eq!(analyze("$()*"), vec!["empty sequences never match any arguments"]);
+    assert_eq!(analyze("$a:()+"), vec!["empty sequences never match any arguments"]);
+}
+
+#[test]
+fn test_analyze_specification_expr() {
+    assert_eq!(analyze("$a:expr + $b:expr"), vec![
+        "`$a:expr` may consume the operator that follows it",
+    ]);
+    assert_eq!(analyze("$a:expr == $b:expr"), vec![
+        "`$a:expr` may consume the operator that follows it",
+    ]);
+    assert_eq!(analyze("$a:expr $b:binop $c:expr"), vec![
+        "`$a:expr` may consume the operator that follows it",
+    ]);
+    assert_eq!(analyze("$($a:expr)(+)*"), vec![
+        "`$a:expr` may consume the operator that follows it",
+    ]);
+    assert_eq!(analyze("$a:expr as i32 + $b:expr"), vec![
+        "`$a:expr as i32` may consume the operator that follows it",
+    ]);
+    assert_eq!(analyze("$a:expr_addr_of + $b:expr"), vec![
+        "`$a:expr_addr_of` may consume the operator that follows it",
+    ]);
+}
+
+#[test]
+fn test_analyze_specification_span() {
+    let warnings = analyze_specification_string("foo $($a:ident)* bar").unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!((warnings[0].0.lo.0, warnings[0].0.hi.0), (4, 16));
+
+    let warnings = analyze_specification_string("$a:lit as u16 $($b:ident)* bar").unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!((warnings[0].0.lo.0, warnings[0].0.hi.0), (14, 26));
+
+    let warnings = analyze_specification_string("$($a:ident)$b:, + $($c:ident)* d").unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!((warnings[0].0.lo.0, warnings[0].0.hi.0), (18, 30));
+
+    let warnings = analyze_specification_string("$a:{A(), B()}").unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!((warnings[0].0.lo.0, warnings[0].0.hi.0), (9, 10));
+}
+
+#[test]
+fn test_analyze_specification_span_docs() {
+    let string = "/// Foo\n$a:ident\n/// Bar\n$($b:ident)* baz";
+    let warnings = analyze_specification_string(string).unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!((warnings[0].0.lo.0, warnings[0].0.hi.0), (25, 37));
+
+    let string = "$a:{\n/// A\nA(),\n/// B\nB()\n}";
+    let warnings = analyze_specification_string(string).unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!((warnings[0].0.lo.0, warnings[0].0.hi.0), (22, 23));
+}
@@ -13,9 +13,9 @@ use easy_plugin_parsers::arguments::*;
 
 use syntax::print::pprust;
 use syntax::ast::*;
-use syntax::codemap::{BytePos, Spanned};
+use syntax::codemap::{self, BytePos, Spanned, DUMMY_SP};
 use syntax::parse::{self, ParseSess};
-use syntax::parse::token::{BinOpToken, DelimToken, Token};
+use syntax::parse::token::{self, BinOpToken, DelimToken, Token};
 use syntax::ptr::{P};
 use syntax::tokenstream::{Delimited, TokenTree};
 
@@ -114,6 +114,17 @@ fn test_parse_arguments_error() {
     assert_error_eq!("$(foo)+", "", 0, 0, "unexpected end of arguments: expected `foo`");
 
     assert_error_eq!("$a:(foo)+", "", 0, 0, "unexpected end of arguments: expected `foo`");
+
+    assert_error_eq!("$a:lit_int as u8", "256", 0, 3, "integer literal is out of range for `u8`");
+    assert_error_eq!("$a:lit as u16", "1i32", 0, 4, "expected `u16` integer literal");
+    assert_error_eq!("$a:expr as u32", "-1", 0, 2, "integer literal is out of range for `u32`");
+    assert_error_eq!("$a:expr as i8", "-129", 0, 4, "integer literal is out of range for `i8`");
+    assert_error_eq!("$a:lit as f32", "1e39", 0, 4, "float literal is out of range for `f32`");
+    assert_error_eq!("$a:lit as f64", "1f32", 0, 4, "expected `f64` float literal");
+    assert_error_eq!("$a:lit as char", "1", 0, 1, "expected character literal");
+    assert_error_eq!("$a:expr as bool", "foo", 0, 3, "expected literal");
+    assert_error_eq!("$b:ident $a:lit_int as u8", "foo 256", 4, 7,
+        "integer literal is out of range for `u8`");
 }
 
 #[test]
@@ -200,6 +211,90 @@ fn test_parse_arguments_extractor() {
     assert_eq!(pprust::ty_to_string(&argument), "i32");
 }
 
+#[test]
+fn test_parse_arguments_conversion() {
+    assert_eq!(parse("$a:lit_int as u16", "8080").unwrap().get::<u16>("a"), 8080);
+    assert_eq!(parse("$a:lit as usize", "42usize").unwrap().get::<usize>("a"), 42);
+    assert_eq!(parse("$a:expr as i8", "-128").unwrap().get::<i8>("a"), -128);
+    let argument = parse("$a:expr as i64", "-(9223372036854775808)").unwrap().get::<i64>("a");
+    assert_eq!(argument, i64::min_value());
+    assert_eq!(parse("$a:lit as f32", "1.5f32").unwrap().get::<f32>("a"), 1.5);
+    assert_eq!(parse("$a:expr as f64", "-2.5").unwrap().get::<f64>("a"), -2.5);
+    assert_eq!(parse("$a:lit_float_unsuffixed as f64", "1_000.5").unwrap().get::<f64>("a"), 1000.5);
+    assert_eq!(parse("$a:lit as bool", "true").unwrap().get::<bool>("a"), true);
+    assert_eq!(parse("$a:lit_char as char", "'x'").unwrap().get::<char>("a"), 'x');
+    assert_eq!(parse("$a:lit as u8", "b'x'").unwrap().get::<u8>("a"), b'x');
+    assert_eq!(parse("$a:lit_str as String", "\"foo\"").unwrap().get::<String>("a"), "foo");
+    assert_eq!(parse("$a:ident as String", "foo").unwrap().get::<String>("a"), "foo");
+}
+
+#[test]
+fn test_select_error() {
+    let error = |lo, hi, message: &str| {
+        (codemap::mk_sp(BytePos(lo), BytePos(hi)), message.to_string())
+    };
+    let errors = vec![
+        error(4, 10, "too many arguments"),
+        error(0, 10, "unexpected end of arguments: expected `;`"),
+        error(7, 8, "expected `=>`"),
+    ];
+    assert_eq!(select_error(errors.clone()).1, "unexpected end of arguments: expected `;`");
+    assert_eq!(select_error(errors[..1].to_vec()).1, "too many arguments");
+    assert_eq!(select_error(vec![errors[2].clone(), errors[0].clone()]).1, "expected `=>`");
+    let errors = vec![error(4, 5, "expected `a`"), error(4, 6, "expected `b`")];
+    assert_eq!(select_error(errors).1, "expected `a`");
+}
+
+#[test]
+fn test_meta_item_arguments() {
+    fn arguments(string: &str) -> (ParseSess, Vec<TokenTree>) {
+        let session = ParseSess::new();
+        let tts = {
+            let name = "<meta>".into();
+            let string = string.into();
+            let mut parser = parse::new_parser_from_source_str(&session, vec![], name, string);
+            let meta = parser.parse_meta_item().unwrap();
+            meta_item_arguments(&session, &meta).unwrap()
+        };
+        (session, tts)
+    }
+
+    assert!(arguments("foo").1.is_empty());
+
+    let (_, tts) = arguments("foo = \"bar\"");
+    assert_eq!(pprust::tts_to_string(&tts), "= \"bar\"");
+    assert_span_eq!(tts[1].get_span(), 6, 11);
+
+    let (_, tts) = arguments("foo(bar, baz = 1u8, qux(true, 'a'))");
+    assert_eq!(pprust::tts_to_string(&tts), "bar , baz = 1u8 , qux ( true , 'a' )");
+    assert_span_eq!(tts[0].get_span(), 4, 7);
+    assert_span_eq!(tts[7].get_span(), 23, 34);
+
+    let (session, tts) = arguments("foo(name = \"bar\", size = 4)");
+    let specification = "name = $name:lit_str, size = $size:lit_int";
+    let specification = specification::parse_specification_string(specification).unwrap();
+    let arguments = parse_arguments(&session, &tts, &specification).unwrap();
+    assert_eq!(arguments.get::<(String, StrStyle)>("name").0, "bar");
+    assert_eq!(arguments.get::<(u64, LitIntType)>("size").0, 4);
+}
+
+#[test]
+fn test_ident_macro_arguments() {
+    let session = ParseSess::new();
+    let source = "foo!  bar { baz }";
+    let filemap = session.codemap().new_filemap("<ident>".into(), None, source.into());
+    let span = codemap::mk_sp(filemap.start_pos, filemap.end_pos);
+    let ident = token::str_to_ident("bar");
+    let tts = ident_macro_arguments(&session, span, ident, vec![]);
+    assert_eq!(tts.len(), 1);
+    assert!(tts[0].eq_token(Token::Ident(ident)));
+    assert_span_eq!(tts[0].get_span(), 6, 9);
+
+    let tts = ident_macro_arguments(&session, DUMMY_SP, ident, vec![tts[0].clone()]);
+    assert_eq!(tts.len(), 2);
+    assert_eq!(tts[0].get_span(), DUMMY_SP);
+}
+
 #[test]
 fn test_parse_arguments_specific() {
     parse("foo + bar", "foo + bar").unwrap();
@@ -228,6 +323,28 @@ fn test_parse_arguments_delimited() {
     assert_eq!(parse("($a:(foo)?)", "(foo)").unwrap().get::<Spanned<bool>>("a").node, true);
 }
 
+#[test]
+fn test_parse_arguments_any_delimited() {
+    macro_rules! assert_delimiter_eq {
+        ($string:expr, $delimiter:expr) => ({
+            let arguments = parse("$a:[$b:ident]", $string).unwrap();
+            let delimiter = arguments.get::<Delimiter>("a");
+            assert_eq!(delimiter.delimiter, $delimiter);
+            assert_span_eq!(delimiter.open_span, 0, 1);
+            assert_span_eq!(delimiter.close_span, 4, 5);
+            assert_eq!(arguments.get::<Spanned<Ident>>("b").node.to_string(), "foo");
+        });
+    }
+
+    assert_delimiter_eq!("{foo}", DelimToken::Brace);
+    assert_delimiter_eq!("[foo]", DelimToken::Bracket);
+    assert_delimiter_eq!("(foo)", DelimToken::Paren);
+
+    assert!(parse("$[foo]", "(foo)").is_ok());
+    assert!(parse("$[foo]", "foo").is_err());
+    assert!(parse("$[foo]", "(foo bar)").is_err());
+}
+
 #[test]
 fn test_parse_arguments_sequence() {
     parse("$()?", "").unwrap();
@@ -307,6 +424,58 @@ fn test_parse_arguments_sequence() {
     assert_eq!(arguments[0].len(), 1);
     assert_eq!(pprust::attribute_to_string(&arguments[0][0]), "#[test]");
     //assert_span_eq!(arguments[0][0].span, 0, 7);
+
+    let arguments = parse("$($($a:ident)* ;)* $b:ident", "foo bar ; baz").unwrap();
+    let a = arguments.get_sequence("a").into_sequence_vec(|s| s.into_vec::<Spanned<Ident>>());
+    assert_eq!(a.len(), 1);
+    assert_eq!(a[0].len(), 2);
+    let b = arguments.get::<Spanned<Ident>>("b");
+    assert_eq!(b.node.to_string(), "baz");
+    assert_span_eq!(b.span, 10, 13);
+}
+
+#[test]
+fn test_parse_arguments_separators() {
+    let arguments = parse("$($a:ident)$b:, *", "foo, bar, baz").unwrap();
+    let b = arguments.get::<Vec<Spanned<Token>>>("b");
+    assert_eq!(b.len(), 2);
+    assert_eq!(b[0].node, Token::Comma);
+    assert_span_eq!(b[0].span, 3, 4);
+    assert_span_eq!(b[1].span, 8, 9);
+
+    let arguments = parse("$($a:ident)$b:, *, ? ;", "foo, bar, ;").unwrap();
+    assert_eq!(arguments.get_sequence("a").into_vec::<Spanned<Ident>>().len(), 2);
+    let b = arguments.get::<Vec<Spanned<Token>>>("b");
+    assert_eq!(b.len(), 2);
+    assert_span_eq!(b[1].span, 8, 9);
+
+    let arguments = parse("$($a:ident)$b:, * , ;", "foo, bar, ;").unwrap();
+    assert_eq!(arguments.get::<Vec<Spanned<Token>>>("b").len(), 1);
+
+    assert!(parse("$($a:ident), *", "foo, bar,").is_err());
+    assert!(parse("$($a:ident), *, ?", "foo, bar,").is_ok());
+
+    let arguments = parse("$a:(foo)$b:, *", "foo, foo").unwrap();
+    assert_eq!(arguments.get::<Spanned<usize>>("a").node, 2);
+    assert_eq!(arguments.get::<Vec<Spanned<Token>>>("b").len(), 1);
+
+    let arguments = parse("$($a:ident)(, and)* ,", "foo, and bar, and baz,").unwrap();
+    assert_eq!(arguments.get_sequence("a").into_vec::<Spanned<Ident>>().len(), 3);
+
+    let arguments = parse("$($a:ident)(, and)*(, and)?", "foo, and bar, and").unwrap();
+    assert_eq!(arguments.get_sequence("a").into_vec::<Spanned<Ident>>().len(), 2);
+
+    let arguments = parse("$($a:ident)($b:binop)+", "foo + bar - baz").unwrap();
+    assert_eq!(arguments.get_sequence("a").into_vec::<Spanned<Ident>>().len(), 3);
+    let b = arguments.get_sequence("b").into_vec::<Spanned<BinOpToken>>();
+    assert_eq!(b.len(), 2);
+    assert_eq!(b[0].node, BinOpToken::Plus);
+    assert_eq!(b[1].node, BinOpToken::Minus);
+    assert_span_eq!(b[1].span, 8, 9);
+
+    let arguments = parse("$($a:ident)($b:binop)* -", "foo + bar -").unwrap();
+    assert_eq!(arguments.get_sequence("a").into_vec::<Spanned<Ident>>().len(), 2);
+    assert_eq!(arguments.get_sequence("b").into_vec::<Spanned<BinOpToken>>().len(), 1);
 }
 
 #[test]
@@ -320,6 +489,25 @@ fn test_parse_arguments_named_sequence() {
     assert_eq!(parse("$a:(foo)*", "foo foo").unwrap().get::<Spanned<usize>>("a").node, 2);
     assert_eq!(parse("$a:(foo)+", "foo").unwrap().get::<Spanned<usize>>("a").node, 1);
     assert_eq!(parse("$a:(foo)+", "foo foo").unwrap().get::<Spanned<usize>>("a").node, 2);
+
+    let arguments = parse("$a:($b:ident: $c:ty), *", "foo: i32, bar: f32").unwrap();
+    let items = arguments.get_sequence("a").into_item_vec();
+    assert_eq!(items.len(), 2);
+    assert_span_eq!(items[0].span, 0, 8);
+    assert_span_eq!(items[1].span, 10, 18);
+    assert_span_eq!(arguments.get_sequence_span("a"), 0, 18);
+    let argument = items[1].arguments.get::<Spanned<Ident>>("b");
+    assert_eq!(argument.node.to_string(), "bar");
+    assert_span_eq!(argument.span, 10, 13);
+    let argument = items[1].arguments.get::<P<Ty>>("c");
+    assert_eq!(pprust::ty_to_string(&argument), "f32");
+
+    let arguments = parse("$a:($b:ident)?", "").unwrap();
+    assert!(arguments.get_sequence("a").into_item_option().is_none());
+    assert_eq!(arguments.get_sequence_span("a"), DUMMY_SP);
+
+    let arguments = parse("$a:(foo)* bar", "bar").unwrap();
+    assert_eq!(arguments.get::<Spanned<usize>>("a").span, DUMMY_SP);
 }
 
 #[test]
@@ -341,4 +529,11 @@ fn test_parse_arguments_enum() {
     let argument = arguments.arguments.get::<Spanned<BinOpToken>>("b");
     assert_eq!(argument.node, BinOpToken::Plus);
     assert_span_eq!(argument.span, 0, 1);
+
+    let arguments = parse("$a:{A(foo $a:ident), B(foo $b:binop)}", "foo +").unwrap();
+    let arguments = arguments.get_enum("a");
+    assert_eq!(arguments.variant, 1);
+    let argument = arguments.arguments.get::<Spanned<BinOpToken>>("b");
+    assert_eq!(argument.node, BinOpToken::Plus);
+    assert_span_eq!(argument.span, 4, 5);
 }
@@ -0,0 +1,99 @@
+#![cfg_attr(not(feature="syntex"), feature(rustc_private))]
+
+extern crate easy_plugin_parsers;
+
+use easy_plugin_parsers::compatibility::*;
+use easy_plugin_parsers::specification::*;
+
+fn compare(old: &str, new: &str) -> Vec<Change> {
+    let old = parse_specification_string(old).unwrap();
+    let new = parse_specification_string(new).unwrap();
+    compare_specifications(&old, &new)
+}
+
+#[test]
+fn test_compare_specifications_none() {
+    assert!(compare("", "").is_empty());
+    assert!(compare("$a:ident $b:expr", "$a:ident $b:expr").is_empty());
+    assert!(compare("$($a:ident)+", "$($a:ident)*").is_empty());
+}
+
+#[test]
+fn test_compare_specifications_fields() {
+    assert_eq!(compare("$a:ident", "$a:ident $($b:expr)?"), vec![
+        Change::FieldAdded("b".into(), "Option<expr>".into()),
+    ]);
+    assert_eq!(compare("$a:{A($b:ident)}", "$a:{A($b:ident), B()}"), vec![
+        Change::FieldAdded("a.B".into(), "variant".into()),
+    ]);
+    assert_eq!(compare("$a:ident", "$a:expr"), vec![
+        Change::FieldRetyped("a".into(), "ident".into(), "expr".into()),
+    ]);
+    assert_eq!(compare("$a:{A(), B}", "$a:Kind{A, B}"), vec![
+        Change::FieldRetyped("a".into(), "enum".into(), "Kind".into()),
+        Change::FieldRetyped("a.A".into(), "variant".into(), "unit variant".into()),
+    ]);
+    assert_eq!(compare("$a:lit_int", "$a:lit_int as u16"), vec![
+        Change::FieldRetyped("a".into(), "lit_int".into(), "u16".into()),
+    ]);
+    assert_eq!(compare("$a:ident", "$($a:ident)*"), vec![
+        Change::FieldRetyped("a".into(), "ident".into(), "Vec<ident>".into()),
+        Change::PieceRemoved("a".into(), "$a:ident".into()),
+    ]);
+    assert_eq!(compare("$a:ident, $b:expr", "$a:ident"), vec![
+        Change::FieldRemoved("b".into(), "expr".into()),
+        Change::PieceRemoved("#1".into(), ",".into()),
+        Change::PieceRemoved("b".into(), "$b:expr".into()),
+    ]);
+}
+
+#[test]
+fn test_compare_specifications_pieces() {
+    assert_eq!(compare("$a:ident", "$a:ident $b:expr"), vec![
+        Change::FieldAdded("b".into(), "expr".into()),
+        Change::PieceRequired("b".into(), "$b:expr".into()),
+    ]);
+    assert_eq!(compare("($a:ident)", "($a:ident ;)"), vec![
+        Change::PieceRequired("#0.#1".into(), ";".into()),
+    ]);
+    assert_eq!(compare("$($a:ident)*", "$($a:ident), *"), vec![
+        Change::PieceRequired("#0.separator.#0".into(), ",".into()),
+    ]);
+    assert_eq!(compare("$($a:ident), *, ?", "$($a:ident), *"), vec![
+        Change::PieceRemoved("#0.separator".into(), ", ?".into()),
+    ]);
+}
+
+#[test]
+fn test_compare_specifications_amounts() {
+    assert_eq!(compare("$($a:ident)*", "$($a:ident)+"), vec![
+        Change::AmountNarrowed("#0".into(), Amount::ZeroOrMore, Amount::OneOrMore),
+    ]);
+    assert_eq!(compare("$b:($a:ident)*", "$b:($a:ident)?"), vec![
+        Change::FieldRetyped("b".into(), "Vec<item>".into(), "Option<item>".into()),
+        Change::AmountNarrowed("b".into(), Amount::ZeroOrMore, Amount::ZeroOrOne),
+    ]);
+    assert_eq!(compare("$s:($a:ident)*", "$s:($a:ident $b:ty)+"), vec![
+        Change::FieldAdded("s.b".into(), "ty".into()),
+        Change::AmountNarrowed("s".into(), Amount::ZeroOrMore, Amount::OneOrMore),
+        Change::PieceRequired("s.b".into(), "$b:ty".into()),
+    ]);
+}
+
+#[test]
+fn test_compare_specifications_enum() {
+    assert_eq!(compare("$e:{A($a:ident), B($b:expr)}", "$e:{A($a:ty)}"), vec![
+        Change::FieldRetyped("e.A.a".into(), "ident".into(), "ty".into()),
+        Change::FieldRemoved("e.B".into(), "variant".into()),
+        Change::FieldRemoved("e.B.b".into(), "expr".into()),
+        Change::PieceRemoved("e.B".into(), "B($b:expr)".into()),
+    ]);
+}
+
+#[test]
+fn test_change_display() {
+    let change = Change::AmountNarrowed("#0".into(), Amount::ZeroOrMore, Amount::OneOrMore);
+    assert_eq!(change.to_string(), "sequence at `#0` was narrowed from `*` to `+`");
+    let change = Change::PieceRequired("b".into(), "$b:expr".into());
+    assert_eq!(change.to_string(), "piece `$b:expr` at `b` is now required");
+}
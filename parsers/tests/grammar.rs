@@ -0,0 +1,86 @@
+#![cfg_attr(not(feature="syntex"), feature(rustc_private))]
+
+extern crate easy_plugin_parsers;
+
+use easy_plugin_parsers::grammar::*;
+use easy_plugin_parsers::specification::*;
+
+fn ebnf(string: &str) -> String {
+    to_ebnf("arguments", &parse_specification_string(string).unwrap())
+}
+
+fn svg(string: &str) -> String {
+    to_railroad_svg(&parse_specification_string(string).unwrap())
+}
+
+#[test]
+fn test_to_ebnf() {
+    assert_eq!(ebnf(""), "arguments = ;\n");
+
+    assert_eq!(ebnf("$a:ident"), "arguments = ident (* a *) ;\n");
+    assert_eq!(ebnf("$a:lit_str"), "arguments = lit_str (* a *) ;\n");
+    assert_eq!(ebnf("foo => $a:expr;"), "arguments = \"foo\", \"=>\", expr (* a *), \";\" ;\n");
+    assert_eq!(ebnf("($a:ident)"), "arguments = \"(\", ident (* a *), \")\" ;\n");
+
+    let any = concat!(
+        "( \"(\", ident (* a *), \")\" | \"[\", ident (* a *), \"]\" | ",
+        "\"{\", ident (* a *), \"}\" )"
+    );
+    assert_eq!(ebnf("$[$a:ident]"), format!("arguments = {} ;\n", any));
+    let string = concat!(
+        "arguments = arguments_d ;\n",
+        "arguments_d = \"(\", \")\" | \"[\", \"]\" | \"{\", \"}\" ;\n",
+    );
+    assert_eq!(ebnf("$d:[]"), string);
+
+    assert_eq!(ebnf(r#""it's""#), "arguments = '\"it', \"'s\", '\"' ;\n");
+}
+
+#[test]
+fn test_to_ebnf_sequence() {
+    assert_eq!(ebnf("$($a:ident)?"), "arguments = [ ( ident (* a *) ) ] ;\n");
+    assert_eq!(ebnf("$($a:ident)*"), "arguments = { ( ident (* a *) ) } ;\n");
+    assert_eq!(ebnf("$($a:ident)+"), "arguments = ( ident (* a *) ), { ( ident (* a *) ) } ;\n");
+
+    let string = "arguments = ( ident (* a *) ), { \",\", ( ident (* a *) ) } ;\n";
+    assert_eq!(ebnf("$($a:ident), +"), string);
+    let string = "arguments = [ ( ident (* a *) ), { \",\", ( ident (* a *) ) } ], [ \",\" ] ;\n";
+    assert_eq!(ebnf("$($a:ident), *, ?"), string);
+    let string = "arguments = [ ( ident (* a *) ), { \",\", \"and\", ( ident (* a *) ) } ] ;\n";
+    assert_eq!(ebnf("$($a:ident)(, and)*"), string);
+
+    let string = "arguments = { arguments_s } ;\narguments_s = ident (* a *) ;\n";
+    assert_eq!(ebnf("$s:($a:ident)*"), string);
+    let string = "arguments = { arguments_ident } ;\narguments_ident = ident (* a *) ;\n";
+    assert_eq!(ebnf("$ident:($a:ident)*"), string);
+}
+
+#[test]
+fn test_to_ebnf_enum() {
+    let string = "arguments = arguments_e, \";\" ;\narguments_e = ident (* a *) | \"foo\" | ;\n";
+    assert_eq!(ebnf("$e:{A($a:ident), B(foo), C()};"), string);
+
+    let string = concat!(
+        "arguments = arguments_d ;\n",
+        "arguments_d_E_b = ident (* x *) ;\n",
+        "arguments_d_F_b = ty (* y *) ;\n",
+        "arguments_d = { arguments_d_E_b } | { arguments_d_F_b } ;\n",
+    );
+    assert_eq!(ebnf("$d:{E($b:($x:ident)*), F($b:($y:ty)*)}"), string);
+}
+
+#[test]
+fn test_to_railroad_svg() {
+    let string = svg("foo");
+    assert!(string.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(string.contains("width=\"107\" height=\"62\""));
+    assert!(string.contains(">foo</text>"));
+    assert!(string.ends_with("</svg>\n"));
+
+    let string = svg("$a:ident < $($b:ty), * $e:{A(&), B()}");
+    assert!(string.contains(">a: ident</text>"));
+    assert!(string.contains(">&lt;</text>"));
+    assert!(string.contains(">b: ty</text>"));
+    assert!(string.contains(">,</text>"));
+    assert!(string.contains(">&amp;</text>"));
+}
@@ -7,9 +7,11 @@ extern crate syntax;
 
 extern crate easy_plugin_parsers;
 
+use easy_plugin_parsers::extractor;
 use easy_plugin_parsers::specification::*;
 
 use syntax::parse::token::{DelimToken, Token};
+use syntax::tokenstream::{TokenTree};
 
 macro_rules! spec {
     ($($variant:ident($($tt:tt)*)), *) => (vec![$(Specifier::$variant($($tt)*)), *]);
@@ -19,6 +21,41 @@ fn parse(string: &str) -> Vec<Specifier> {
     parse_specification_string(string).unwrap()
 }
 
+#[test]
+fn test_parse_specification_error() {
+    macro_rules! assert_error_eq {
+        ($string:expr, $message:expr) => ({
+            match parse_specification_string($string) {
+                Err((_, message)) => assert_eq!(message, $message),
+                _ => panic!("expected error"),
+            }
+        });
+    }
+
+    assert_error_eq!("$a:ident )", "unexpected close delimiter: `)`");
+    assert_error_eq!("$a:foo", "invalid named specifier type");
+    assert_error_eq!("$a:ident $a:ident", "duplicate named specifier");
+    assert_error_eq!("$a:[$a:ident]", "duplicate named specifier");
+    assert_error_eq!("${}", "expected named specifier, unnamed sequence, or `[`");
+    assert_error_eq!(
+        "$a:($span:ident)*", "`span` is a reserved named specifier name in named sequences"
+    );
+    assert_error_eq!("$($a:ident)$b:?", "expected separator");
+    assert_error_eq!("$($a:ident)$a:, *", "duplicate named specifier");
+    assert_error_eq!("$($a:ident)(, $a:ident)*", "duplicate named specifier");
+    assert_error_eq!("$($a:ident)$b:(, and)*", "named separators must be a single token");
+    assert_error_eq!("$($a:ident)()*", "empty separators are disallowed");
+    assert_error_eq!("/// Foo.\nfoo", "expected named specifier after doc comment");
+    assert_error_eq!("$a:ident /// Foo.", "expected named specifier after doc comment");
+    assert_error_eq!("$a:{A(), /// Foo.\n}", "expected variant name after doc comment");
+    assert_error_eq!("$a:{#!(foo) A()}", "expected `[`");
+    assert_error_eq!("$a:{A(), #![foo] B()}", "expected variant name");
+    assert_error_eq!("$a:Kind", "invalid named specifier type");
+    assert_error_eq!("$a:{A B()}", "expected `,`");
+    assert_error_eq!("$a:lit_str as u16", "`lit_str` values cannot be converted into `u16`");
+    assert_error_eq!("$a:ty as String", "`ty` values cannot be converted into `String`");
+}
+
 #[test]
 fn test_parse_specification_empty() {
     assert_eq!(parse(""), spec![]);
@@ -72,6 +109,20 @@ fn test_parse_specification_extractor() {
     assert_extractor_eq!("tt_delimited", Tt);
 }
 
+#[test]
+fn test_parse_specification_conversion() {
+    let conversion = Conversion::new(Box::new(Specifier::Lit("a".into())), "u16".into());
+    assert_eq!(parse("$a:lit as u16"), spec![Conversion("a".into(), conversion)]);
+
+    let extractor = Extractor::new(Box::new(Specifier::Lit("a".into())), "lit_int".into());
+    let specifier = Box::new(Specifier::Extractor("a".into(), extractor));
+    let conversion = Conversion::new(specifier, "i64".into());
+    assert_eq!(parse("$a:lit_int as i64"), spec![Conversion("a".into(), conversion)]);
+
+    assert_eq!(parse("$a:ident as $b:ty"), spec![Ident("a".into()), ident("as"), Ty("b".into())]);
+    assert_eq!(parse("$a:ident as Foo"), spec![Ident("a".into()), ident("as"), ident("Foo")]);
+}
+
 #[test]
 fn test_parse_specification_specific() {
     assert_eq!(parse("<"), spec![Specific(Token::Lt)]);
@@ -113,6 +164,18 @@ fn test_parse_specification_delimited() {
     assert_delimited_eq!("($a:()?)", DelimToken::Paren, spec![Sequence(name, sequence)]);
 }
 
+#[test]
+fn test_parse_specification_any_delimited() {
+    assert_eq!(parse("$[]"), spec![AnyDelimited(None, spec![])]);
+    assert_eq!(parse("$a:[]"), spec![AnyDelimited(Some("a".into()), spec![])]);
+
+    let specification = spec![Ident("b".into()), Specific(Token::Comma)];
+    assert_eq!(parse("$a:[$b:ident,]"), spec![AnyDelimited(Some("a".into()), specification)]);
+
+    let sequence = Sequence::new(Amount::ZeroOrMore, None, spec![Tt("a".into())]);
+    assert_eq!(parse("$[$($a:tt)*]"), spec![AnyDelimited(None, spec![Sequence(None, sequence)])]);
+}
+
 #[test]
 fn test_parse_specification_sequence() {
     macro_rules! assert_sequence_eq {
@@ -145,6 +208,45 @@ fn test_parse_specification_sequence() {
     let name = Some("a".into());
     let sequence = Sequence::new(Amount::ZeroOrOne, None, spec![]);
     assert_sequence_eq!("$($a:()?)?", Amount::ZeroOrOne, None, spec![Sequence(name, sequence)]);
+
+    let specification = spec![Expr("a".into())];
+    let mut sequence = Sequence::new(Amount::ZeroOrMore, Some(Token::Comma), specification);
+    sequence.trailing = true;
+    assert_eq!(parse("$($a:expr), *, ?"), spec![Sequence(None, sequence)]);
+
+    // Only the separator of the sequence followed by `?` is a trailing separator.
+    let specification = spec![Expr("a".into())];
+    let sequence = Sequence::new(Amount::ZeroOrMore, Some(Token::Comma), specification);
+    let specification = spec![
+        Sequence(None, sequence), Specific(Token::Semi), Specific(Token::Question)
+    ];
+    assert_eq!(parse("$($a:expr), *; ?"), specification);
+
+    let specification = spec![Expr("a".into())];
+    let mut sequence = Sequence::new(Amount::OneOrMore, Some(Token::Comma), specification);
+    sequence.separator_name = Some("b".into());
+    assert_eq!(parse("$($a:expr)$b:, +"), spec![Sequence(None, sequence.clone())]);
+    sequence.trailing = true;
+    assert_eq!(parse("$($a:expr)$b:, +, ?"), spec![Sequence(None, sequence)]);
+
+    let mut sequence = Sequence::new(Amount::ZeroOrMore, Some(Token::Comma), spec![]);
+    sequence.separator_name = Some("b".into());
+    assert_eq!(parse("$a:()$b:, *"), spec![Sequence(Some("a".into()), sequence)]);
+
+    let mut sequence = Sequence::new(Amount::ZeroOrMore, None, spec![Expr("a".into())]);
+    sequence.separator = Some(spec![Specific(Token::Comma), ident("and")]);
+    assert_eq!(parse("$($a:expr)(, and)*"), spec![Sequence(None, sequence.clone())]);
+    sequence.trailing = true;
+    assert_eq!(parse("$($a:expr)(, and)*(, and)?"), spec![Sequence(None, sequence)]);
+
+    let mut sequence = Sequence::new(Amount::OneOrMore, None, spec![Expr("a".into())]);
+    sequence.separator = Some(spec![Specific(Token::Comma), BinOp("b".into())]);
+    assert_eq!(parse("$($a:expr)(, $b:binop)+"), spec![Sequence(None, sequence)]);
+
+    let specification = spec![Expr("a".into())];
+    let mut sequence = Sequence::new(Amount::ZeroOrMore, Some(Token::FatArrow), specification);
+    sequence.separator_name = Some("b".into());
+    assert_eq!(parse("$($a:expr)$b:(=>)*"), spec![Sequence(None, sequence)]);
 }
 
 #[test]
@@ -172,6 +274,10 @@ fn test_parse_specification_named_sequence() {
 
     let sequence = Sequence::new(Amount::ZeroOrOne, None, spec![]);
     assert_sequence_eq!("$a:($()?)?", Amount::ZeroOrOne, None, spec![Sequence(None, sequence)]);
+
+    let specification = spec![Ident("a".into()), Specific(Token::Colon), Ty("b".into())];
+    let string = "$a:($a:ident: $b:ty), *";
+    assert_sequence_eq!(string, Amount::ZeroOrMore, Some(Token::Comma), specification);
 }
 
 #[test]
@@ -185,9 +291,11 @@ fn test_parse_specification_enum() {
             let specification = parse($string);
             assert_eq!(specification.len(), 1);
             match specification[0] {
-                Specifier::Enum(ref name, ref variants) => {
+                Specifier::Enum(ref name, ref enum_) => {
                     assert_eq!(name, "a");
-                    assert_eq!(variants, &vec![$(Variant::new($name.into(), $specification)), +]);
+                    assert_eq!(enum_.ty, None);
+                    let variants = vec![$(Variant::new($name.into(), $specification)), +];
+                    assert_eq!(enum_.variants, variants);
                 },
                 _ => panic!("expected enumerated specifier"),
             }
@@ -224,3 +332,144 @@ fn test_parse_specification_enum() {
         ("B", spec![Sequence(Some("b".into()), b)]),
     ]);
 }
+
+#[test]
+fn test_parse_specification_enum_type() {
+    let variants = vec![
+        Variant::unit("A".into()),
+        Variant::new("B".into(), spec![Ident("b".into())]),
+        Variant::unit("C".into()),
+    ];
+    assert_eq!(parse("$a:Kind{A, B($b:ident), C}"), spec![
+        Enum("a".into(), Enum::new(Some("Kind".into()), variants.clone())),
+    ]);
+    assert_eq!(parse("$a:{A, B($b:ident), C,}"), spec![
+        Enum("a".into(), Enum::new(None, variants)),
+    ]);
+    assert_eq!(parse("$a:ident {foo}"), spec![
+        Ident("a".into()),
+        Delimited(Delimited::new(DelimToken::Brace, spec![ident("foo")])),
+    ]);
+}
+
+#[test]
+fn test_parse_specification_docs() {
+    let string = "
+        /// An identifier.
+        /// Another line.
+        $a:ident
+        /// A sequence.
+        $b:(/// An expression.\n$c:expr), *
+        /// An enum.
+        $d:{/// A variant.\nE(/// A type.\n$f:ty), G()}
+    ";
+    let (_, docs) = parse_specification_string_with_docs(string).unwrap();
+    let (_, metadata) = parse_specification_string_with_metadata(string).unwrap();
+    assert_eq!(docs, metadata.docs);
+    let mut docs = docs.into_iter().collect::<Vec<_>>();
+    docs.sort();
+    let docs = docs.iter().map(|&(ref p, ref d)| {
+        (&p[..], d.iter().map(|d| &d[..]).collect::<Vec<_>>())
+    }).collect::<Vec<_>>();
+    assert_eq!(docs, vec![
+        ("a", vec![" An identifier.", " Another line."]),
+        ("b", vec![" A sequence."]),
+        ("b.c", vec![" An expression."]),
+        ("d", vec![" An enum."]),
+        ("d.E", vec![" A variant."]),
+        ("d.E.f", vec![" A type."]),
+    ]);
+    let (_, docs) = parse_specification_string_with_docs("$a:ident // Foo.").unwrap();
+    assert!(docs.is_empty());
+}
+
+#[test]
+fn test_parse_specification_attributes() {
+    let string = "$a:{#![derive(PartialEq)] #![allow(dead_code)] A()} $b:($c:{B()})*";
+    let (specification, metadata) = parse_specification_string_with_metadata(string).unwrap();
+    assert_eq!(specification, parse("$a:{A()} $b:($c:{B()})*"));
+    assert_eq!(metadata.attributes.keys().collect::<Vec<_>>(), &["a"]);
+    let attributes = &metadata.attributes["a"];
+    assert_eq!(attributes.len(), 4);
+    assert!(attributes[0].eq_token(Token::Pound) && attributes[2].eq_token(Token::Pound));
+    match attributes[1] {
+        TokenTree::Delimited(_, ref delimited) => {
+            assert_eq!(delimited.delim, DelimToken::Bracket);
+            assert_eq!(delimited.tts.len(), 2);
+        },
+        _ => panic!("expected delimited token tree"),
+    }
+    let (_, metadata) = parse_specification_string_with_metadata("$a:{A()}").unwrap();
+    assert!(metadata.attributes.is_empty());
+}
+
+#[test]
+fn test_to_specification_string() {
+    macro_rules! assert_string_eq {
+        ($string:expr, $expected:expr) => ({
+            assert_eq!(to_specification_string(&parse($string)), $expected);
+        });
+    }
+
+    assert_string_eq!("", "");
+    assert_string_eq!("$a:ident", "$a:ident");
+    assert_string_eq!("$a:lit_str", "$a:lit_str");
+    assert_string_eq!("foo => $a:expr;", "foo => $a:expr ;");
+    assert_string_eq!("($a:ident, [$b:ty])", "($a:ident , [$b:ty])");
+    assert_string_eq!("$[$a:ident] $b:[]", "$[$a:ident] $b:[]");
+    assert_string_eq!("$($a:ident),*", "$($a:ident), *");
+    assert_string_eq!("$($a:ident)$b:;+;?", "$($a:ident)$b:; +; ?");
+    assert_string_eq!("$($a:ident)$b: ::*", "$($a:ident)$b: :: *");
+    assert_string_eq!("$($a:ident)(+)+", "$($a:ident)(+) +");
+    assert_string_eq!("$($a:ident)(, and)*(, and)?", "$($a:ident)(, and) *(, and) ?");
+    assert_string_eq!("$a:(pub)?", "$a:(pub)?");
+    assert_string_eq!("$a:{A($a:ident), B()}", "$a:{A($a:ident), B()}");
+    assert_string_eq!("$a:Kind{A, B($b:ident)}", "$a:Kind{A, B($b:ident)}");
+    assert_string_eq!("$a:lit_int as u16", "$a:lit_int as u16");
+}
+
+#[test]
+fn test_to_specification_string_round_trip() {
+    let fragments = [
+        "", "foo", "=>", ";", "'a", "1", "\"foo\"", "$N:ident", "$N:lit_str", "$N:tt", "()",
+        "[$N:expr]", "{foo $N:ty}", "$[$N:pat]", "$N:[= $Ma:ident]", "$($N:ident)*",
+        "$($N:ident)+", "$($N:ident)?", "$($N:ident), *", "$($N:ident)$M:; +",
+        "$($N:ident), *, ?", "$($N:ident)$M:; *; ?", "$($N:ident)(+)*", "$($N:ident)(?)*",
+        "$($N:ident)(, $M:ident and)+", "$N:(foo $a:ident)*", "$N:($a:ident)$M:, +",
+        "$N:{A(), B($a:expr), C($($b:tt)*)}", "$N:lit_int as u16", "$N:ident as String",
+    ];
+
+    for (i, a) in fragments.iter().enumerate() {
+        for (j, b) in fragments.iter().enumerate() {
+            let a = a.replace("$N", &format!("$a{}", i)).replace("$M", &format!("$b{}", i));
+            let b = b.replace("$N", &format!("$c{}", j)).replace("$M", &format!("$d{}", j));
+            for string in &[format!("{} {}", a, b), format!("$({} {})*", a, b)] {
+                let specification = parse(string);
+                let printed = to_specification_string(&specification);
+                assert_eq!(parse(&printed), specification, "{} => {}", string, printed);
+                let reprinted = to_specification_string(&parse(&printed));
+                assert_eq!(reprinted, printed);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_extractor_to_string_round_trip() {
+    for extractor in extractor::EXTRACTORS {
+        let specification = parse(&format!("$a:{}", extractor));
+        let printed = match specification[0] {
+            Specifier::Extractor(_, ref extractor) => extractor.to_string(),
+            ref specifier => panic!("expected extractor specifier: {:?}", specifier),
+        };
+        assert_eq!(parse(&printed), specification, "{}", printed);
+    }
+
+    let specification = parse("$a:lit_str as String");
+    let printed = match specification[0] {
+        Specifier::Conversion(_, ref conversion) => conversion.to_string(),
+        ref specifier => panic!("expected conversion specifier: {:?}", specifier),
+    };
+    assert_eq!(printed, "$a:lit_str as String");
+    assert_eq!(parse(&printed), specification);
+}
@@ -0,0 +1,30 @@
+#![cfg_attr(not(feature="syntex"), feature(rustc_private))]
+
+extern crate easy_plugin_parsers;
+
+use easy_plugin_parsers::specification::*;
+use easy_plugin_parsers::usage::*;
+
+fn usage(string: &str) -> String {
+    Usage(&parse_specification_string(string).unwrap()).to_string()
+}
+
+#[test]
+fn test_usage() {
+    assert_eq!(usage(""), "");
+
+    assert_eq!(usage("$a:ident"), "a: ident");
+    assert_eq!(usage("$a:lit_str"), "a: lit_str");
+
+    assert_eq!(usage("foo => $a:expr;"), "foo => a: expr;");
+    assert_eq!(usage("($a:ident, $b:ty)"), "( a: ident, b: ty )");
+    assert_eq!(usage("$[$a:ident]"), "delimited( a: ident )");
+
+    assert_eq!(usage("$name:ident, $($field:ty), *"), "name: ident, [ field: ty ],*");
+    assert_eq!(usage("$($a:ident)?"), "[ a: ident ]?");
+    assert_eq!(usage("$($a:ident)$b:; +; ?"), "[ a: ident ];+;?");
+    assert_eq!(usage("$($a:ident)(, and)*"), "[ a: ident ](, and)*");
+    assert_eq!(usage("$a:(pub)?"), "[ pub ]?");
+
+    assert_eq!(usage("$a:{A($a:ident), B(), C(foo $b:meta)}"), "[ a: ident | | foo b: meta ]");
+}
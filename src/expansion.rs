@@ -0,0 +1,228 @@
+// Copyright 2016 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Position-aware plugin results.
+
+use std::fmt;
+
+use syntax::ast::{self, Expr, Item, Pat, Stmt, StmtKind, Ty};
+use syntax::codemap::{Span};
+use syntax::ext::base::{DummyResult, ExtCtxt, MacResult};
+use syntax::ptr::{P};
+use syntax::util::small_vector::{SmallVector};
+
+//================================================
+// Structs
+//================================================
+
+// Builder _______________________________________
+
+/// A function that builds a value with an expansion context.
+struct Builder<T>(Box<FnMut(&mut ExtCtxt) -> Option<T>>);
+
+impl<T> Builder<T> {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `Builder` from the supplied function.
+    fn new<F: FnOnce(&mut ExtCtxt) -> T + 'static>(f: F) -> Builder<T> {
+        let mut f = Some(f);
+        Builder(Box::new(move |context| f.take().map(|f| f(context))))
+    }
+
+    //- Consumers --------------------------------
+
+    /// Builds the value with the supplied expansion context.
+    fn build(mut self, context: &mut ExtCtxt) -> T {
+        (self.0)(context).unwrap()
+    }
+}
+
+// Expansion _____________________________________
+
+/// The result of a plugin that expands to different AST nodes depending on where it is invoked.
+///
+/// A builder is supplied for each position the plugin supports (e.g., expressions or items) and
+/// only the builder for the position the plugin is invoked in is called. Builders are passed the
+/// expansion context the plugin was invoked with so they can build AST nodes with it. If the plugin
+/// is invoked in a position it does not support, an error listing the positions it supports is
+/// reported.
+pub struct Expansion {
+    span: Span,
+    expr: Option<Builder<P<Expr>>>,
+    items: Option<Builder<Vec<P<Item>>>>,
+    pat: Option<Builder<P<Pat>>>,
+    stmts: Option<Builder<Vec<Stmt>>>,
+    ty: Option<Builder<P<Ty>>>,
+}
+
+impl Expansion {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `Expansion` for a plugin invoked at the supplied span.
+    ///
+    /// The `Expansion` does not support any positions until builders are supplied for them.
+    pub fn new(span: Span) -> Expansion {
+        Expansion { span: span, expr: None, items: None, pat: None, stmts: None, ty: None }
+    }
+
+    //- Consumers --------------------------------
+
+    /// Returns this `Expansion` with the supplied expression builder.
+    pub fn expr<F: FnOnce(&mut ExtCtxt) -> P<Expr> + 'static>(mut self, f: F) -> Expansion {
+        self.expr = Some(Builder::new(f));
+        self
+    }
+
+    /// Returns this `Expansion` with the supplied item builder.
+    pub fn items<F: FnOnce(&mut ExtCtxt) -> Vec<P<Item>> + 'static>(mut self, f: F) -> Expansion {
+        self.items = Some(Builder::new(f));
+        self
+    }
+
+    /// Returns this `Expansion` with the supplied pattern builder.
+    pub fn pat<F: FnOnce(&mut ExtCtxt) -> P<Pat> + 'static>(mut self, f: F) -> Expansion {
+        self.pat = Some(Builder::new(f));
+        self
+    }
+
+    /// Returns this `Expansion` with the supplied statement builder.
+    ///
+    /// If a statement builder is not supplied, the expression builder or the item builder is used
+    /// in statement position instead.
+    pub fn stmts<F: FnOnce(&mut ExtCtxt) -> Vec<Stmt> + 'static>(mut self, f: F) -> Expansion {
+        self.stmts = Some(Builder::new(f));
+        self
+    }
+
+    /// Returns this `Expansion` with the supplied type builder.
+    pub fn ty<F: FnOnce(&mut ExtCtxt) -> P<Ty> + 'static>(mut self, f: F) -> Expansion {
+        self.ty = Some(Builder::new(f));
+        self
+    }
+
+    /// Returns a `MacResult` for this `Expansion` that builds AST nodes and reports errors with the
+    /// supplied expansion context.
+    pub fn into_mac_result<'cx>(self, context: &'cx mut ExtCtxt) -> Box<MacResult + 'cx> {
+        Box::new(ExpansionResult { expansion: self, context: context })
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns descriptions of the positions this `Expansion` supports.
+    fn get_positions(&self) -> Vec<&'static str> {
+        let mut positions = vec![];
+        if self.expr.is_some() {
+            positions.push("an expression");
+        }
+        if self.items.is_some() {
+            positions.push("items");
+        }
+        if self.pat.is_some() {
+            positions.push("a pattern");
+        }
+        if self.stmts.is_some() || self.expr.is_some() || self.items.is_some() {
+            positions.push("a statement");
+        }
+        if self.ty.is_some() {
+            positions.push("a type");
+        }
+        positions
+    }
+
+    /// Reports that this `Expansion` does not support the supplied position.
+    fn report(&self, context: &ExtCtxt, position: &str) {
+        let message = format!("this plugin cannot be used as {}", position);
+        let mut error = context.struct_span_err(self.span, &message);
+        let positions = self.get_positions();
+        if !positions.is_empty() {
+            error.note(&format!("this plugin can be used as {}", positions.join(", ")));
+        }
+        error.emit();
+    }
+}
+
+impl fmt::Debug for Expansion {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let positions = self.get_positions();
+        write!(formatter, "Expansion {{ span: {:?}, positions: {:?} }}", self.span, positions)
+    }
+}
+
+// ExpansionResult _______________________________
+
+/// A `MacResult` that builds the AST nodes for an `Expansion` on demand.
+struct ExpansionResult<'cx, 'a: 'cx> {
+    expansion: Expansion,
+    context: &'cx mut ExtCtxt<'a>,
+}
+
+impl<'cx, 'a: 'cx> MacResult for ExpansionResult<'cx, 'a> {
+    fn make_expr(self: Box<Self>) -> Option<P<Expr>> {
+        let ExpansionResult { expansion, context } = *self;
+        if expansion.expr.is_none() {
+            expansion.report(context, "an expression");
+            return Some(DummyResult::raw_expr(expansion.span));
+        }
+        expansion.expr.map(|b| b.build(context))
+    }
+
+    fn make_items(self: Box<Self>) -> Option<SmallVector<P<Item>>> {
+        let ExpansionResult { expansion, context } = *self;
+        if expansion.items.is_none() {
+            expansion.report(context, "items");
+            return Some(SmallVector::zero());
+        }
+        expansion.items.map(|b| SmallVector::many(b.build(context)))
+    }
+
+    fn make_pat(self: Box<Self>) -> Option<P<Pat>> {
+        let ExpansionResult { expansion, context } = *self;
+        if expansion.pat.is_none() {
+            expansion.report(context, "a pattern");
+            return Some(P(DummyResult::raw_pat(expansion.span)));
+        }
+        expansion.pat.map(|b| b.build(context))
+    }
+
+    fn make_stmts(self: Box<Self>) -> Option<SmallVector<Stmt>> {
+        let ExpansionResult { expansion, context } = *self;
+        if expansion.stmts.is_some() {
+            expansion.stmts.map(|b| SmallVector::many(b.build(context)))
+        } else if expansion.expr.is_some() {
+            expansion.expr.map(|b| {
+                let expr = b.build(context);
+                let span = expr.span;
+                let stmt = Stmt { id: ast::DUMMY_NODE_ID, node: StmtKind::Expr(expr), span: span };
+                SmallVector::one(stmt)
+            })
+        } else if expansion.items.is_some() {
+            expansion.items.map(|b| b.build(context).into_iter().map(|i| {
+                let span = i.span;
+                Stmt { id: ast::DUMMY_NODE_ID, node: StmtKind::Item(i), span: span }
+            }).collect())
+        } else {
+            expansion.report(context, "a statement");
+            Some(SmallVector::zero())
+        }
+    }
+
+    fn make_ty(self: Box<Self>) -> Option<P<Ty>> {
+        let ExpansionResult { expansion, context } = *self;
+        if expansion.ty.is_none() {
+            expansion.report(context, "a type");
+            return Some(DummyResult::raw_ty(expansion.span));
+        }
+        expansion.ty.map(|b| b.build(context))
+    }
+}
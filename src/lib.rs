@@ -61,6 +61,9 @@
 //! `a` in the `Arguments` struct. For more information on argument specifications, see the relevant
 //! section [below](#specifications).
 //!
+//! The generated `Arguments` struct also has an associated function, `specification`, which returns
+//! the argument specification as a `Vec<Specifier>` without any parsing at runtime.
+//!
 //! If the arguments do not match the argument specification or your plugin function returns `Err`,
 //! the wrapper function will report an error with `ExtCtxt::span_err` for you.
 //!
@@ -102,6 +105,26 @@
 //!
 //! [extractor]: extractor/index.html
 //!
+//! ## Any Delimiters
+//!
+//! Delimited token trees in plugin argument specifications match only the delimiter they are
+//! written with. Argument specifications enclosed in `$[` and `]` instead match the enclosed
+//! argument specification delimited by any delimiter. For example, the following plugin argument
+//! specification matches `(foo)`, `[foo]`, or `{foo}`.
+//!
+//! ```ignore
+//! $[$a:ident]
+//! ```
+//!
+//! The delimiter can be stored by naming it. For example, the following plugin argument
+//! specification stores the delimiter in `delimiter`. The storage type for named delimiters is
+//! [`Delimiter`](struct.Delimiter.html), which contains the kind of delimiter that was found and
+//! the spans of the opening and closing delimiters.
+//!
+//! ```ignore
+//! $delimiter:[$a:ident]
+//! ```
+//!
 //! ## Sequences
 //!
 //! Plugin argument specifications support sequences that are very similar to the sequences in macro
@@ -127,6 +150,41 @@
 //! base storage type. `Vec<$type>` is used for `*` and `+` sequences and `Option<$type>` is used
 //! for `?` sequences.
 //!
+//! The separator of a `*` or `+` sequence can also be a parenthesized argument specification,
+//! which allows for separators that consist of more than one token. For example, the following
+//! plugin argument specification matches one or more expressions separated by `, and`.
+//!
+//! ```ignore
+//! $($expr:expr)(, and)+
+//! ```
+//!
+//! Separator argument specifications may contain named specifiers. The storage types for these
+//! named specifiers are `Vec<$type>` where `$type` is the base storage type, with one element for
+//! each separator that was matched.
+//!
+//! The separators of `*` and `+` sequences can be stored by naming them. For example, the following
+//! plugin argument specification stores the commas that separate the expressions in `commas`. The
+//! storage type for named separators is `Vec<Spanned<Token>>`. Only separators that consist of a
+//! single token can be named.
+//!
+//! ```ignore
+//! $($expr:expr)$commas:, *
+//! ```
+//!
+//! A trailing separator can be permitted by following a `*` or `+` sequence with the separator and
+//! the `?` operator. For example, the following plugin argument specification matches zero or more
+//! comma-separated expressions which may be followed by a comma. A trailing separator that is
+//! found is stored along with the other separators if the separators are named.
+//!
+//! ```ignore
+//! $($expr:expr), *, ?
+//! ```
+//!
+//! Because of this, a separator followed by `?` immediately after a `*` or `+` sequence is always
+//! interpreted as a trailing separator rather than as the separator and a `?` token. Argument
+//! specifications for earlier versions of this crate that used these tokens literally (e.g., the
+//! above plugin argument specification intended to match `a, b, ?`) now have a different meaning.
+//!
 //! An additional level of `Vec` is added for each sequence level. For example, in the plugin
 //! argument specification below, `$b:ident` occurs two sequences deep. The storage type for `b` in
 //! this case would be `Vec<Vec<syntax::ast::Ident>>`.
@@ -138,9 +196,9 @@
 //! ## Named Sequences
 //!
 //! There are also named sequences, which behave rather differently than regular sequences. Named
-//! sequences cannot contain named specifiers and instead consist of specific token trees that you
-//! wish to be counted. For example, the following plugin argument specification will match
-//! either `pub struct { }` or just `struct { }`.
+//! sequences that do not contain named specifiers consist of specific token trees that you wish to
+//! be counted. For example, the following plugin argument specification will match either
+//! `pub struct { }` or just `struct { }`.
 //!
 //! ```ignore
 //! $public:(pub)? struct { }
@@ -157,6 +215,31 @@
 //! Because named sequences are counted, the storage types are simply `usize` for `*` and `+` named
 //! sequences and `bool` for `?`named sequences.
 //!
+//! Named sequences that do contain named specifiers are not counted. Instead, each occurrence of
+//! the named sequence is stored in an instance of a generated struct. For example, the following
+//! plugin argument specification matches any number of comma-separated fields.
+//!
+//! ```ignore
+//! $fields:($name:ident: $ty:ty), *
+//! ```
+//!
+//! The storage type for `fields` above would be `Spanned<Vec<fields_Item>>` where `fields_Item`
+//! is the following struct. `Spanned<Vec<$item>>` is used for `*` and `+` named sequences and
+//! `Spanned<Option<$item>>` is used for `?` named sequences. The span is the span of the entire
+//! named sequence (or `DUMMY_SP` if there are no occurrences).
+//!
+//! ```ignore
+//! #[derive(Clone, Debug)]
+//! struct fields_Item {
+//!     span: Span,
+//!     name: Spanned<Ident>,
+//!     ty: P<Ty>,
+//! }
+//! ```
+//!
+//! The `span` field contains the span of the occurrence, so `span` cannot be used as the name of a
+//! named specifier in a named sequence.
+//!
 //! ## Enums
 //!
 //! There are also enumerated specifiers, which allow for a choice of possible values. For example,
@@ -170,12 +253,433 @@
 //! for `e` above would be the following enum.
 //!
 //! ```ignore
-//! #[derive(Debug)]
+//! #[derive(Clone, Debug)]
 //! enum e_Enum {
 //!     A { a: Spanned<Ident> },
 //!     B { b: P<MetaItem> },
 //! }
 //! ```
+//!
+//! The name of the generated enum may be given before the variants and variants that match nothing
+//! may be written without an argument specification, in which case they are generated as unit
+//! variants. For example, the storage type for `e` in the plugin argument specification below
+//! would be the following enum.
+//!
+//! ```ignore
+//! $e:Kind{A($a:ident), B}
+//! ```
+//!
+//! ```ignore
+//! #[derive(Clone, Debug)]
+//! enum Kind {
+//!     A { a: Spanned<Ident> },
+//!     B,
+//! }
+//! ```
+//!
+//! The fields of the generated enums have the same visibility as the enums themselves, so the
+//! values of enumerated specifiers can be matched outside of the wrapper function when the
+//! argument struct is emitted at the item level (see [below](#item-level-arguments)).
+//!
+//! ## Conversions
+//!
+//! Simple and extractor named specifiers may be followed by a conversion clause, in which case the
+//! parsed value is converted into a native type with [`Convert`](convert/trait.Convert.html) and
+//! the storage type for the specifier is the native type. Conversion errors (e.g., integer literals
+//! that are out of range or have the wrong suffix) are reported at the converted argument. For
+//! example, the storage type for `port` in the plugin argument specification below would be `u16`.
+//!
+//! ```ignore
+//! $host:ident as String : $port:lit_int as u16
+//! ```
+//!
+//! | Name                   | Native Types                                                |
+//! |:-----------------------|:------------------------------------------------------------|
+//! | `expr` and `lit`       | integers, `f32`, `f64`, `bool`, `char`, and `String`        |
+//! | `ident` and `lit_str`  | `String`                                                    |
+//! | `lit_bool`             | `bool`                                                      |
+//! | `lit_byte`             | `u8`                                                        |
+//! | `lit_char`             | `char`                                                      |
+//! | `lit_float`            | `f32` and `f64`                                             |
+//! | `lit_float_unsuffixed` | `f32` and `f64`                                             |
+//! | `lit_int`              | integers                                                    |
+//!
+//! Expressions are only converted if they are literals, optionally negated or parenthesized
+//! (e.g., `-1`).
+//!
+//! `as` following a simple or extractor named specifier always begins a conversion clause when it
+//! is followed by one of the native types above, so such a specifier can't be followed by the
+//! literal tokens `as` and a native type (e.g., `$a:ident as String` is a conversion and `$a:ty as
+//! String` is an error). `as` followed by anything else is matched literally as before (e.g., `as`
+//! and `Foo` are literal tokens in `$a:ident as Foo`).
+//!
+//! # Usage
+//!
+//! A human-readable usage string is generated from each plugin argument specification using
+//! [`Usage`](struct.Usage.html). Errors that occur while parsing plugin arguments are reported
+//! with a note containing the usage of the plugin. The usage string is also available through the
+//! `USAGE` associated constant of the generated argument struct. For example, the usage string for
+//! the plugin argument specification below would be `name: ident, [ field: ty ],*`.
+//!
+//! ```ignore
+//! $name:ident, $($field:ty), *
+//! ```
+//!
+//! # Documentation
+//!
+//! Generated plugin wrappers are documented with the usage of the plugin, under the name it is
+//! registered with by `easy_plugin_registrar!`, in addition to any documentation on the plugin
+//! function. Doc comments may also precede named specifiers and the
+//! variants of enumerated specifiers, in which case they are copied onto the corresponding fields
+//! and variants of the generated argument structs and enums. The documentation for a plugin
+//! argument specification can be retrieved with
+//! [`parse_specification_with_docs`](fn.parse_specification_with_docs.html).
+//!
+//! ```ignore
+//! /// The name of the struct.
+//! $name:ident,
+//! /// The types of the fields of the struct.
+//! $($field:ty), *
+//! ```
+//!
+//! # Help
+//!
+//! Invoking a plugin with a single `?` as its arguments (e.g., `plugin!(?)`) emits a note that
+//! contains the documentation comment of the plugin function and the usage of the plugin instead
+//! of calling the plugin function. This only applies when the plugin arguments cannot be parsed
+//! from a single `?`, so plugins with argument specifications that accept a single `?` (e.g.,
+//! `$($tt:tt)*`) are called with it as usual. Identifier plugins emit the note when the identifier
+//! is followed by a single `?` (e.g., `plugin! foo (?)`). This does not apply to attribute
+//! plugins.
+//!
+//! # Attributes
+//!
+//! The generated argument structs and enums derive `Clone` and `Debug`. Attributes may be
+//! placed on `struct Arguments` in the `easy_plugin!` input and inner attributes may be placed
+//! before the variants of enumerated specifiers, in which case they are copied onto the generated
+//! argument struct and enums respectively. For example, the generated argument enum for the plugin
+//! argument specification below would also implement `PartialEq`.
+//!
+//! ```ignore
+//! $e:{#![derive(PartialEq)] A($a:ident), B($b:lit)}
+//! ```
+//!
+//! `Clone` and `Debug` are not derived for the argument struct or enum if it has the
+//! `#[easy_plugin(no_derive)]` attribute, in which case they are not derived for any of the
+//! generated argument structs and enums, or for the enum generated for an enumerated specifier if
+//! it has the `#![easy_plugin(no_derive)]` inner attribute. They are also not derived for
+//! generated argument structs and enums that contain an enum or named sequence for which they are
+//! not derived.
+//!
+//! ```ignore
+//! $e:{#![easy_plugin(no_derive)] A($a:ident), B($b:lit)}
+//! ```
+//!
+//! # Item-Level Arguments
+//!
+//! By default, the generated argument struct and enums are emitted in the body of the wrapper
+//! function. If `struct Arguments` has the `#[easy_plugin(module)]` attribute, they are instead
+//! emitted alongside the wrapper function so they can be named elsewhere. In this case, the
+//! argument struct and enums have the visibility given on `struct Arguments` and arguments can be
+//! parsed outside of the wrapper function with the `parse` associated function of the argument
+//! struct.
+//!
+//! The argument struct and enums are emitted in a generated module named after the argument struct
+//! (e.g., `__easy_plugin_Arguments`) whose contents are imported with a glob import. Because the
+//! generated names of enums (e.g., `e_Enum`) would conflict between plugins emitted in the same
+//! module, enumerated specifiers in these plugins must specify the names of their enums (e.g.,
+//! `$e:Kind{A($a:ident), B($b:lit)}`).
+//!
+//! ```ignore
+//! easy_plugin! {
+//!     #[easy_plugin(module)]
+//!     pub struct Arguments { $a:ident }
+//!
+//!     pub fn expand_plugin(
+//!         context: &mut ExtCtxt, span: Span, arguments: Arguments
+//!     ) -> PluginResult<Box<MacResult>> {
+//!         // ...
+//!     }
+//! }
+//!
+//! let arguments = try!(Arguments::parse(session, tts));
+//! ```
+//!
+//! # Alternatives
+//!
+//! Plugins that accept arguments of completely different shapes can declare an argument enum
+//! instead of an argument struct. Each variant of the argument enum contains an alternative plugin
+//! argument specification, and an argument struct is generated for each alternative (named by
+//! appending the variant name to the enum name). The alternatives are tried in order and the
+//! arguments for the first alternative that accepts the plugin arguments are passed to the plugin
+//! function. If no alternative accepts the plugin arguments, the error that occurred furthest into
+//! the plugin arguments is reported with a note containing the usage of each alternative.
+//!
+//! ```ignore
+//! easy_plugin! {
+//!     enum Arguments {
+//!         Name { $name:ident },
+//!         Body { $name:ident => $body:block },
+//!     }
+//!
+//!     pub fn expand_plugin(
+//!         context: &mut ExtCtxt, span: Span, arguments: Arguments
+//!     ) -> PluginResult<Box<MacResult>> {
+//!         match arguments {
+//!             Arguments::Name(arguments) => { /* `arguments` is an `ArgumentsName` */ },
+//!             Arguments::Body(arguments) => { /* `arguments` is an `ArgumentsBody` */ },
+//!         }
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! Enumerated specifiers and named sequences generate types and functions named after them, so
+//! these must have different names in different alternatives.
+//!
+//! # Inline Specifications
+//!
+//! Plugins with small argument specifications can declare the plugin argument specification in
+//! the parameter list of the plugin function instead of in an argument struct. The first two
+//! parameters are the names of the `&mut ExtCtxt` and `Span` parameters and the remaining
+//! parameters are the pieces of the plugin argument specification, separated by commas. Each named
+//! specifier becomes a parameter of the same name with the type it would have as a field of an
+//! argument struct.
+//!
+//! ```ignore
+//! easy_plugin! {
+//!     fn expand_plugin(context, span, $a:ident, =>, $b:expr) -> PluginResult<P<Expr>> {
+//!         // `a` is a `Spanned<Ident>` and `b` is a `P<Expr>`
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! The commas that separate parameters are not part of the plugin argument specification, so the
+//! above plugin is invoked with arguments such as `foo => 1 + 2`. A comma is part of the plugin
+//! argument specification instead of separating parameters if it immediately follows
+//!
+//! * the parenthesized body of a sequence (e.g., `$($a:ident), *`),
+//! * the name of a named separator (e.g., `$($a:ident)$b:, *`),
+//! * the `*` or `+` of a comma-separated sequence and precedes `?` (e.g., `$($a:ident), *, ?`),
+//! * or a comma that separates parameters.
+//!
+//! The last rule means that a comma is written as a parameter by doubling the comma that precedes
+//! it. For example, `fn expand_plugin(context, span, $a:ident,, $b:ident)` has the plugin argument
+//! specification `$a:ident, $b:ident`.
+//!
+//! The plugin function must declare a return type, may be preceded by doc comments and
+//! attributes, and may be `pub`, `pub(crate)`, or `unsafe`. Because the wrapper function is called
+//! by the compiler, it is always safe to call, so `unsafe` only makes the body of the plugin
+//! function an unsafe context. An argument struct is still generated for the plugin argument
+//! specification, but it has a reserved name (e.g., `__EasyPluginExpandPluginArguments` for the
+//! above plugin) so that it does not conflict with other items. Inline specifications are not
+//! supported by attribute plugins.
+//!
+//! # Return Types
+//!
+//! Plugin functions may return the AST nodes they expand to instead of a `Box<MacResult>`, in
+//! which case the wrapper function wraps them in a `MacResult` for you. The supported return types
+//! are listed below.
+//!
+//! | Return Type                  | Expands To                                      |
+//! |:-----------------------------|:------------------------------------------------|
+//! | `PluginResult<P<Expr>>`      | An expression (`MacEager::expr`).               |
+//! | `PluginResult<Vec<P<Item>>>` | Items (`MacEager::items`).                      |
+//! | `PluginResult<P<Pat>>`       | A pattern (`MacEager::pat`).                    |
+//! | `PluginResult<Vec<Stmt>>`    | Statements (`MacEager::stmts`).                 |
+//! | `PluginResult<P<Ty>>`        | A type (`MacEager::ty`).                        |
+//! | `PluginResult<MacEager>`     | Whichever of the above the `MacEager` contains. |
+//! | `PluginResult<Expansion>`    | See [below](#position-aware-expansions).        |
+//!
+//! The return type is determined from the signature of the plugin function, so these types must be
+//! written as above (paths such as `ast::Expr` are also accepted). Any other return type is
+//! expected to be `PluginResult<Box<MacResult>>`.
+//!
+//! ```ignore
+//! easy_plugin! {
+//!     struct Arguments { $a:expr }
+//!
+//!     pub fn expand_plugin(
+//!         context: &mut ExtCtxt, span: Span, arguments: Arguments
+//!     ) -> PluginResult<P<Expr>> {
+//!         Ok(arguments.a)
+//!     }
+//! }
+//! ```
+//!
+//! # Position-Aware Expansions
+//!
+//! Plugins that can be invoked in more than one position (e.g., both as an expression and at the
+//! module level) can return an [`Expansion`](struct.Expansion.html), which contains a builder for
+//! each position the plugin supports. Only the builder for the position the plugin is actually
+//! invoked in is called (with the expansion context the plugin was invoked with), and invoking the
+//! plugin in a position without a builder reports an error that lists the positions the plugin
+//! supports.
+//!
+//! ```ignore
+//! easy_plugin! {
+//!     struct Arguments { $name:ident }
+//!
+//!     pub fn expand_plugin(
+//!         _: &mut ExtCtxt, span: Span, arguments: Arguments
+//!     ) -> PluginResult<Expansion> {
+//!         let name = arguments.name.node;
+//!         Ok(Expansion::new(span)
+//!             .expr(|context| quote_expr!(context, 42))
+//!             .items(move |context| vec![quote_item!(context, const $name: i32 = 42;).unwrap()]))
+//!     }
+//! }
+//! ```
+//!
+//! # Identifier Plugins
+//!
+//! If the argument struct or enum has the `#[easy_plugin(ident)]` attribute, the wrapper function
+//! is generated for a macro that is invoked with an identifier (e.g., `foo! bar { ... }`) instead.
+//! In this case, the plugin argument specification must begin with an `ident` specifier which
+//! captures the identifier and the rest of the specification is applied to the delimited plugin
+//! arguments that follow it. The identifier and the arguments are combined with
+//! [`ident_macro_arguments`](fn.ident_macro_arguments.html) before they are parsed. The wrapper
+//! function can be registered as a `SyntaxExtension::IdentTT`.
+//!
+//! ```ignore
+//! easy_plugin! {
+//!     #[easy_plugin(ident)]
+//!     struct Arguments { $name:ident $($field:ident: $ty:ty), * }
+//!
+//!     pub fn expand_record(
+//!         context: &mut ExtCtxt, span: Span, arguments: Arguments
+//!     ) -> PluginResult<Box<MacResult>> {
+//!         // ...
+//!     }
+//! }
+//!
+//! #[plugin_registrar]
+//! pub fn plugin_registrar(registry: &mut Registry) {
+//!     let extension = SyntaxExtension::IdentTT(Box::new(expand_record), None, false);
+//!     registry.register_syntax_extension(token::intern("record"), extension);
+//! }
+//! ```
+//!
+//! # Attribute Plugins
+//!
+//! If the argument struct or enum has the `#[easy_plugin(decorator)]` or
+//! `#[easy_plugin(modifier)]` attribute, the wrapper function is generated for an attribute plugin
+//! instead of a macro. In this case, the plugin arguments are the arguments of the attribute
+//! (e.g., `name = "x"` for `#[table(name = "x")]`) and the plugin function is also passed the item
+//! the attribute is attached to.
+//!
+//! The plugin function of a decorator is passed a reference to the item and returns the
+//! additional items to emit alongside it, and the wrapper function can be registered as a
+//! `SyntaxExtension::MultiDecorator`.
+//!
+//! ```ignore
+//! easy_plugin! {
+//!     #[easy_plugin(decorator)]
+//!     struct Arguments { name = $name:lit_str }
+//!
+//!     pub fn expand_table(
+//!         context: &mut ExtCtxt, span: Span, arguments: Arguments, annotatable: &Annotatable
+//!     ) -> PluginResult<Vec<Annotatable>> {
+//!         // ...
+//!     }
+//! }
+//!
+//! #[plugin_registrar]
+//! pub fn plugin_registrar(registry: &mut Registry) {
+//!     let extension = SyntaxExtension::MultiDecorator(Box::new(expand_table));
+//!     registry.register_syntax_extension(token::intern("table"), extension);
+//! }
+//! ```
+//!
+//! The plugin function of a modifier is passed the item and returns the items to replace it
+//! with, and the wrapper function can be registered as a `SyntaxExtension::MultiModifier`.
+//!
+//! ```ignore
+//! easy_plugin! {
+//!     #[easy_plugin(modifier)]
+//!     struct Arguments { name = $name:lit_str }
+//!
+//!     pub fn expand_table(
+//!         context: &mut ExtCtxt, span: Span, arguments: Arguments, annotatable: Annotatable
+//!     ) -> PluginResult<Vec<Annotatable>> {
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! The arguments of an attribute are converted into token trees by
+//! [`meta_item_arguments`](fn.meta_item_arguments.html) before they are parsed. If the arguments
+//! cannot be parsed or the plugin function returns an error, a modifier leaves the item it is
+//! attached to unchanged.
+//!
+//! # Registration
+//!
+//! `easy_plugin_registrar!` accepts a sequence of items, expands the `easy_plugin!` invocations
+//! among them, and generates a `plugin_registrar` function that adds each of the generated plugin
+//! functions to a `Registry` (using `#[plugin_registrar]` with nightly Rust and as a function that
+//! accepts a `syntex::Registry` with syntex). Each plugin function is registered with the name
+//! given by the `#[easy_plugin(name = "...")]` attribute of its argument struct or enum or, if
+//! there is no such attribute, the name of the plugin function without the `expand_` prefix.
+//!
+//! ```ignore
+//! easy_plugin_registrar! {
+//!     easy_plugin! {
+//!         struct Arguments { $a:ident }
+//!
+//!         /// Registered as `plugin!`.
+//!         pub fn expand_plugin(
+//!             context: &mut ExtCtxt, span: Span, arguments: Arguments
+//!         ) -> PluginResult<Box<MacResult>> {
+//!             // ...
+//!         }
+//!     }
+//!
+//!     easy_plugin! {
+//!         #[easy_plugin(decorator, name = "table")]
+//!         struct Arguments { name = $name:lit_str }
+//!
+//!         /// Registered as `#[table(...)]`.
+//!         pub fn expand_table_decorator(
+//!             context: &mut ExtCtxt, span: Span, arguments: Arguments, annotatable: &Annotatable
+//!         ) -> PluginResult<Vec<Annotatable>> {
+//!             // ...
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The generated `plugin_registrar` function refers to `::rustc_plugin::Registry`, so crates that
+//! use syntex should import it as `extern crate syntex as rustc_plugin;`.
+//!
+//! # Analysis
+//!
+//! Plugin argument specifications are analyzed by
+//! [`analyze_specification`](fn.analyze_specification.html) when they are expanded and warnings
+//! are emitted for pieces that can never match as intended. Warnings are emitted for enumerated
+//! specifier variants that are shadowed by earlier variants, sequences that may consume the
+//! arguments intended for the pieces that follow them, sequences that are empty or may repeat
+//! without matching any arguments, and expressions that may consume the operators that follow
+//! them. For example, a warning would be emitted for the plugin argument specification below
+//! because `foo` would be consumed by the sequence.
+//!
+//! ```ignore
+//! $($name:ident)* foo
+//! ```
+//!
+//! # Grammars
+//!
+//! Plugin argument specifications can be rendered as EBNF using [`to_ebnf`](fn.to_ebnf.html) and
+//! as self-contained SVG railroad diagrams using [`to_railroad_svg`](fn.to_railroad_svg.html).
+//! Both accept argument specifications parsed by
+//! [`parse_specification_string`](fn.parse_specification_string.html) so they can be used in
+//! build scripts to generate documentation that matches the plugin argument specifications.
+//!
+//! ```ignore
+//! let specification = parse_specification_string("$name:ident, $($field:ty), *").unwrap();
+//! let ebnf = to_ebnf("arguments", &specification);
+//! let svg = to_railroad_svg(&specification);
+//! ```
 
 #![cfg_attr(not(feature="syntex"), feature(plugin, plugin_registrar, rustc_private))]
 
@@ -202,10 +706,20 @@ extern crate rustc_errors as rustc_errors;
 extern crate easy_plugin_parsers as parsers;
 extern crate synthax;
 
+pub use parsers::convert;
 pub use parsers::extractor;
 pub use parsers::{PluginResult};
+#[doc(hidden)]
+pub use parsers::{TransactionParser};
+pub use parsers::analysis::*;
 pub use parsers::arguments::*;
+pub use parsers::compatibility::*;
+pub use parsers::grammar::*;
 pub use parsers::specification::*;
+pub use parsers::usage::*;
+
+mod expansion;
+pub use expansion::{Expansion};
 
 mod utility;
 pub use utility::{PluginResultExt, ToError};